@@ -0,0 +1,62 @@
+// achievement.rs 🦀
+// named goals the engine unlocks as it sees them happen
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    CopKiller,
+    DebtFree,
+    JustSayNo,
+    LoneWolf,
+    Stuffed,
+    // kingpin until it clashed with the rank of the same name
+    #[serde(alias = "kingpin")]
+    TopDog,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 6] = [
+        Achievement::CopKiller,
+        Achievement::DebtFree,
+        Achievement::JustSayNo,
+        Achievement::LoneWolf,
+        Achievement::Stuffed,
+        Achievement::TopDog,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Achievement::CopKiller => "COP KILLER",
+            Achievement::DebtFree => "DEBT FREE",
+            Achievement::JustSayNo => "JUST SAY NO",
+            Achievement::LoneWolf => "LONE WOLF",
+            Achievement::Stuffed => "STUFFED",
+            Achievement::TopDog => "TOP DOG",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::CopKiller => "killed every cop on your tail",
+            Achievement::DebtFree => "paid the loan shark back in full",
+            Achievement::JustSayNo => "turned down the paraquat weed",
+            Achievement::LoneWolf => "survived without ever seeing the loan shark",
+            Achievement::Stuffed => "survived with every pocket full",
+            Achievement::TopDog => "finished with a score of 100",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_old_kingpin_is_a_top_dog() {
+        let old: Achievement = serde_json::from_str("\"kingpin\"").unwrap();
+        assert_eq!(old, Achievement::TopDog);
+        assert_eq!(serde_json::to_string(&old).unwrap(), "\"top_dog\"");
+    }
+}
@@ -0,0 +1,220 @@
+// catalog.rs 🦀
+// the drugs on the street, the classic six live in data/drugs.toml
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::drug::Drug;
+use crate::locale::Locale;
+
+static CLASSIC: &str = include_str!("../data/drugs.toml");
+
+fn ten() -> i64 {
+    10
+}
+
+fn hundred() -> i64 {
+    100
+}
+
+fn magenta() -> String {
+    "magenta".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrugSpec {
+    pub name: String,
+    pub key: char,
+    pub min_price: i64,
+    pub max_price: i64,
+    #[serde(default = "ten")]
+    pub step: i64,
+    #[serde(default = "magenta")]
+    pub color: String,
+    // only matter when drugs are limited, the chance in 100 a place has
+    // none on a day & the most it sells & buys in one
+    #[serde(default)]
+    pub scarcity: i64,
+    #[serde(default = "hundred")]
+    pub supply: i64,
+    #[serde(default = "hundred")]
+    pub demand: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    #[serde(rename = "drug")]
+    drugs: Vec<DrugSpec>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Catalog {
+    pub fn classic() -> Self {
+        Self::from_toml(CLASSIC).unwrap()
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let catalog: Catalog = toml::from_str(text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        catalog.validate()?;
+        Ok(catalog)
+    }
+
+    pub(crate) fn validate(&self) -> io::Result<()> {
+        let bad = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+
+        if self.drugs.is_empty() {
+            return bad("the catalog has no drugs".to_string());
+        }
+        let mut keys = HashSet::new();
+        for spec in &self.drugs {
+            let key = spec.key.to_ascii_lowercase();
+            if !key.is_ascii_alphabetic() {
+                return bad(format!("{}: key must be a letter", spec.name));
+            }
+            if !keys.insert(key) {
+                return bad(format!("{}: key {:?} is taken", spec.name, spec.key));
+            }
+            if spec.step <= 0 || spec.min_price < spec.step || spec.max_price < spec.min_price {
+                return bad(format!(
+                    "{}: needs 0 < step <= min_price <= max_price",
+                    spec.name
+                ));
+            }
+            if !(0..=100).contains(&spec.scarcity) {
+                return bad(format!("{}: scarcity must be 0 to 100", spec.name));
+            }
+            if spec.supply <= 0 || spec.demand <= 0 {
+                return bad(format!("{}: supply & demand must be positive", spec.name));
+            }
+        }
+        Ok(())
+    }
+
+    // the keys a language gives its drugs, checked like the catalog's own
+    pub fn localize(&mut self, locale: &Locale) -> io::Result<()> {
+        for spec in &mut self.drugs {
+            if let Some(key) = locale.drug_key(&spec.name) {
+                spec.key = key;
+            }
+        }
+        self.validate()?;
+        self.check_keys(locale.key("pause"))
+    }
+
+    // pause is heard at every prompt, a drug can't go by it
+    pub fn check_keys(&self, pause: char) -> io::Result<()> {
+        match self.from_key(pause) {
+            Some(drug) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: key {:?} is the pause key", self.name(drug), pause),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.drugs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drugs.is_empty()
+    }
+
+    pub fn drugs(&self) -> impl Iterator<Item = Drug> {
+        (0..self.drugs.len()).map(Drug)
+    }
+
+    pub fn spec(&self, drug: Drug) -> &DrugSpec {
+        &self.drugs[drug.0]
+    }
+
+    pub fn name(&self, drug: Drug) -> &str {
+        &self.drugs[drug.0].name
+    }
+
+    pub fn find(&self, name: &str) -> Option<Drug> {
+        self.drugs
+            .iter()
+            .position(|spec| spec.name.eq_ignore_ascii_case(name))
+            .map(Drug)
+    }
+
+    pub fn from_key(&self, c: char) -> Option<Drug> {
+        let c = c.to_ascii_lowercase();
+        self.drugs
+            .iter()
+            .position(|spec| spec.key.to_ascii_lowercase() == c)
+            .map(Drug)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // one drug, with a line of it swapped for another
+    fn one(from: &str, to: &str) -> io::Result<Catalog> {
+        let drug = "[[drug]]\nname = \"GLUE\"\nkey = \"g\"\nmin_price = 10\nmax_price = 50\n";
+        Catalog::from_toml(&drug.replace(from, to))
+    }
+
+    #[test]
+    fn drugs_go_by_name_or_key_in_any_case() {
+        let catalog = Catalog::classic();
+        let cocaine = catalog.find("COCAINE").unwrap();
+        assert_eq!(catalog.find("Cocaine"), Some(cocaine));
+        assert_eq!(catalog.from_key('C'), Some(cocaine));
+        assert_eq!(catalog.find("GLUE"), None);
+
+        let glue = one("", "").unwrap();
+        let spec = glue.spec(glue.find("glue").unwrap());
+        assert_eq!((spec.step, spec.scarcity, spec.supply), (10, 0, 100));
+    }
+
+    #[test]
+    fn a_bad_catalog_is_turned_away() {
+        assert!(Catalog::from_toml("").is_err());
+        for (from, to) in [
+            ("key = \"g\"", "key = \"7\""),
+            ("min_price = 10", "min_price = 60"),
+            ("min_price = 10", "min_price = 5"),
+            ("max_price = 50", "max_price = 50\nscarcity = 101"),
+            ("max_price = 50", "max_price = 50\nsupply = 0"),
+        ] {
+            assert!(one(from, to).is_err(), "{to}");
+        }
+        // two drugs on one key, whatever its case
+        let text = include_str!("../data/drugs.toml").replace("key = \"h\"", "key = \"C\"");
+        assert!(Catalog::from_toml(&text).is_err());
+    }
+
+    #[test]
+    fn a_language_can_rekey_a_drug() {
+        let mut catalog = Catalog::classic();
+        let weed = catalog.find("WEED").unwrap();
+        catalog.localize(&Locale::built_in("es").unwrap()).unwrap();
+        assert_eq!(catalog.from_key('m'), Some(weed));
+        assert_eq!(catalog.from_key('w'), None);
+    }
+
+    #[test]
+    fn no_drug_goes_by_the_pause_key() {
+        let english = include_str!("../data/locales/en.toml");
+        let locale = Locale::from_toml(&english.replace("pause = \"q\"", "pause = \"w\"")).unwrap();
+        assert!(Catalog::classic().localize(&locale).is_err());
+        assert!(Catalog::classic().check_keys('q').is_ok());
+    }
+}
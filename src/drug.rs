@@ -0,0 +1,13 @@
+// drug.rs 🦀
+
+use serde::{Deserialize, Serialize};
+
+// a drug in the game's catalog, the index of its [[drug]] entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Drug(pub(crate) usize);
+
+impl Drug {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
@@ -7,11 +7,27 @@
 #![allow(clippy::explicit_iter_loop)]
 #![allow(clippy::unused_self)]
 
-use rand::Rng;
-use std::collections::HashMap;
+mod term;
+mod theme;
+#[cfg(feature = "tui")]
+mod tui;
+
+use drugwars::highscores::{self, HighScore, SortBy};
+use drugwars::journal::{self, Input, JournalWriter};
+use drugwars::locale;
+use drugwars::profile;
+use drugwars::{
+    save, Achievement, Catalog, Command, Difficulty, Drug, End, Error, Event, Events, Game,
+    Locale, Location, Map, Market, Offer, Rules, Setup,
+};
+use std::collections::VecDeque;
 use std::env;
-use std::io::{self, Write};
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::{Mutex, OnceLock};
 use std::thread;
 use std::time::Duration;
 
@@ -28,1422 +44,2342 @@ use {
     winapi::um::winnt::HANDLE,
 };
 
-#[cfg(unix)]
-use {
-    nix::libc::STDIN_FILENO,
-    nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg},
-};
-
 use chrono::{Datelike, Local, NaiveDate};
 use terminal_size::{terminal_size, Height, Width};
 
+static PLAIN: AtomicBool = AtomicBool::new(false);
+// for a screen reader, the screen only ever grows & the huds are plain lines
+static READER: AtomicBool = AtomicBool::new(false);
+// the words, keys & numbers of the language being played in
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+// set by SIGWINCH, picked up by the next key read
+static RESIZED: AtomicBool = AtomicBool::new(false);
+
+// what's been printed since the last clear, & the screen it was painted on,
+// so a resize can put it all back
+struct Screen {
+    text: String,
+    painter: Option<fn(&mut Client)>,
+    painted: usize,
+}
+
+impl Screen {
+    const fn blank() -> Self {
+        Screen {
+            text: String::new(),
+            painter: None,
+            painted: 0,
+        }
+    }
+}
+
+static SCREEN: Mutex<Screen> = Mutex::new(Screen::blank());
+
+// print! & println! that drop the colors in plain mode & recolor them for
+// the theme
+macro_rules! emit {
+    ($($arg:tt)*) => {
+        put(&render(format!($($arg)*)))
+    };
+}
+
+macro_rules! emitln {
+    () => {
+        put("\n")
+    };
+    ($($arg:tt)*) => {
+        put(&(render(format!($($arg)*)) + "\n"))
+    };
+}
+
+// a message from the locale, with its {name}s filled in from the rest
+macro_rules! t {
+    ($key:expr) => {
+        locale().text($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        locale().fill($key, &[$((stringify!($name), &$value as &dyn fmt::Display)),+])
+    };
+}
+
+fn locale() -> &'static Locale {
+    LOCALE.get_or_init(Locale::english)
+}
+
+// a hotkey in the locale, lowercase like the replies it's checked against
+fn key(name: &str) -> char {
+    locale().key(name)
+}
+
+// the same, the way it's shown on a menu
+fn hotkey(name: &str) -> char {
+    key(name).to_ascii_uppercase()
+}
+
+fn put(s: &str) {
+    SCREEN.lock().unwrap().text.push_str(s);
+    print!("{s}");
+}
+
+fn render(s: String) -> String {
+    let plain = PLAIN.load(AtomicOrdering::Relaxed);
+    if !plain && theme::current() == theme::Theme::Classic {
+        return s;
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1B' {
+            out.push(c);
+            continue;
+        }
+        let Some(next) = chars.next() else { break };
+        if next != '[' {
+            if !plain {
+                out.push(c);
+                out.push(next);
+            }
+            continue;
+        }
+        let mut params = String::new();
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                match c {
+                    _ if plain => {}
+                    'm' => out.push_str(&theme::sgr(&params)),
+                    c => out.push_str(&format!("\x1B[{params}{c}")),
+                }
+                break;
+            }
+            params.push(c);
+        }
+    }
+    out
+}
+
+// no clears & no cursor tricks
+fn linear() -> bool {
+    PLAIN.load(AtomicOrdering::Relaxed) || READER.load(AtomicOrdering::Relaxed)
+}
+
+// red green yellow blue magenta cyan white, or #rrggbb for truecolor
+fn color_code(color: &str) -> String {
+    let code = match color.to_ascii_lowercase().as_str() {
+        "black" => "30",
+        "red" => "31",
+        "green" => "32",
+        "yellow" => "33",
+        "blue" => "34",
+        "magenta" => "35",
+        "cyan" => "36",
+        "white" => "37",
+        hex => {
+            let rgb = hex
+                .strip_prefix('#')
+                .filter(|hex| hex.len() == 6)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok());
+            return match rgb {
+                Some(rgb) => format!(
+                    "\x1B[38;2;{};{};{}m",
+                    rgb >> 16,
+                    (rgb >> 8) & 0xff,
+                    rgb & 0xff
+                ),
+                None => String::new(),
+            };
+        }
+    };
+    format!("\x1B[{code}m")
+}
+
 fn clear_screen() {
-    if cfg!(windows) {
+    *SCREEN.lock().unwrap() = Screen::blank();
+    if linear() {
+        emitln!();
+    } else if cfg!(windows) {
         process::Command::new("cmd")
             .args(["/C", "cls"])
             .status()
             .unwrap();
     } else {
-        print!("\x1B[2J\x1B[1;1H");
+        emit!("\x1B[2J\x1B[1;1H");
         io::stdout().flush().unwrap();
     }
 }
 
 static VERSION: &str = "0.6.15";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Drug {
-    Cocaine,
-    Heroin,
-    Acid,
-    Weed,
-    Speed,
-    Ludes,
-}
-
-impl Drug {
-    fn as_str(&self) -> &'static str {
-        match self {
-            Drug::Cocaine => "COCAINE",
-            Drug::Heroin => "HEROIN",
-            Drug::Acid => "ACID",
-            Drug::Weed => "WEED",
-            Drug::Speed => "SPEED",
-            Drug::Ludes => "LUDES",
-        }
-    }
-}
+// the hud is 56 wide & the menus under it a little wider
+const MIN_WIDTH: usize = 60;
 
-use std::cmp::Ordering;
 use std::fs::File;
 use std::io::{BufReader, BufRead};
 
-#[derive(Debug, Clone)]
-struct HighScore {
-    total_money: i64,
-    dealer_rank: String,
-    date: String,
-}
-
-impl PartialEq for HighScore {
-    fn eq(&self, other: &Self) -> bool {
-        self.total_money == other.total_money
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Title,
+    Instructions,
+    Begin,
+    Loan,
+    Repay,
+    Borrow,
+    Stash,
+    Stashing,
+    StashDeposit(Drug),
+    StashTake(Drug),
+    Banking,
+    Bank,
+    Informant,
+    Tip,
+    Market,
+    Buying,
+    BuyDrug(Drug),
+    Selling,
+    SellDrug(Drug),
+    Jet,
+    Save,
+    History,
+    Offer(Offer),
+    Fight,
+    Scores(Option<Difficulty>, SortBy),
+    Profiles(usize),
+    GameOver,
 }
 
-impl Eq for HighScore {}
+// the window changed size while we were waiting on a key
+#[derive(Debug)]
+struct Resized;
 
-impl PartialOrd for HighScore {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(other.total_money.cmp(&self.total_money))
+impl fmt::Display for Resized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the terminal was resized")
     }
 }
 
-impl Ord for HighScore {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.total_money.cmp(&self.total_money)
-    }
+impl std::error::Error for Resized {}
+
+// a save keeps the drug keys it was played with, none can be this language's pause
+fn load_game(path: &Path) -> io::Result<Game> {
+    let game = save::load(path)?;
+    game.catalog().check_keys(key("pause"))?;
+    Ok(game)
 }
 
-struct GameState {
-    day: i64,
-    days_left: i64,
-    geo: String,
-    cash: i64,
-    guns: i64,
-    bank: i64,
-    debt: i64,
-    maxloan: i64,
-    hold: i64,
-    stash: HashMap<Drug, i64>,
-    trench_coat: HashMap<Drug, i64>,
-    state: String,
-    damage: i64,
-    cops: i64,
-    prices: HashMap<Drug, i64>,
-    width: usize,
-    height: usize,
-    wid: usize,
-    gunprice: i64,
-    coatspace: i64,
-    coatprice: i64,
+fn is_resize(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|e| e.is::<Resized>())
 }
 
-impl GameState {
-    fn new() -> Self {
-        let mut game = GameState {
-            day: 0,
-            days_left: 31,
-            geo: "BRONX".to_string(),
-            cash: 2000,
-            guns: 0,
-            bank: 0,
-            debt: 5500,
-            maxloan: 9450,
-            hold: 100,
-            stash: HashMap::new(),
-            trench_coat: HashMap::new(),
-            state: "begin".to_string(),
-            damage: 0,
-            cops: 0,
-            prices: HashMap::new(),
-            width: 80,
-            height: 24,
-            wid: 40,
-            gunprice: 0,
-            coatspace: 0,
-            coatprice: 0,
-        };
+#[cfg(unix)]
+fn watch_resize() {
+    use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
 
-        for drug in [
-            Drug::Cocaine,
-            Drug::Heroin,
-            Drug::Acid,
-            Drug::Weed,
-            Drug::Speed,
-            Drug::Ludes,
-        ]
-        .iter()
-        {
-            game.stash.insert(*drug, 0);
-            game.trench_coat.insert(*drug, 0);
-            game.prices.insert(*drug, 0);
-        }
+    extern "C" fn resized(_: i32) {
+        RESIZED.store(true, AtomicOrdering::Relaxed);
+    }
 
-        game.term_info();
-        game
+    // no SA_RESTART, so a blocked read wakes up with EINTR
+    let action = SigAction::new(SigHandler::Handler(resized), SaFlags::empty(), SigSet::empty());
+    unsafe {
+        let _ = sigaction(Signal::SIGWINCH, &action);
     }
+}
 
-    fn roll_prices(&mut self) {
-        let mut rng = rand::thread_rng();
+// windows hands resizes over as console input events instead
+#[cfg(not(unix))]
+fn watch_resize() {}
+
+// check turns what was typed into an amount or says what's wrong with it,
+// an amount of None is one given up on
+type Check<'a> = &'a dyn Fn(&str) -> Result<i64, String>;
+
+trait InputSource {
+    fn key(&mut self) -> io::Result<char>;
+    // text is what's been typed so far, it survives a resize or a pause
+    fn amount(&mut self, text: &mut String, check: Check) -> io::Result<Option<i64>>;
+    fn line(&mut self) -> io::Result<String>;
+    // a key from whoever's at the keyboard now, for the pause menu, which
+    // never goes in a journal
+    fn live_key(&mut self) -> io::Result<char> {
+        self.key()
+    }
+}
 
-        let price_ranges = [
-            (Drug::Cocaine, 1500..=3000),
-            (Drug::Heroin, 500..=1400),
-            (Drug::Acid, 100..=450),
-            (Drug::Weed, 30..=90),
-            (Drug::Speed, 7..=25),
-            (Drug::Ludes, 1..=6),
-        ];
+// the keyboard, ctrl-c comes back as an Interrupted error & a resize as
+// a Resized one
+struct Terminal;
 
-        for (drug, range) in price_ranges.iter() {
-            let price = rng.gen_range(range.clone()) * 10;
-            self.prices.insert(*drug, price);
+impl InputSource for Terminal {
+    // a key at a time, backspace rubs out, esc gives up & a bad amount gets
+    // its complaint next to it until the next key
+    fn amount(&mut self, text: &mut String, check: Check) -> io::Result<Option<i64>> {
+        let mut complaint = false;
+        loop {
+            let c = self.key()?;
+            if complaint {
+                emit!("\x1B[K");
+                complaint = false;
+            }
+            match c {
+                '\r' | '\n' => match check(text) {
+                    Ok(amount) => {
+                        emitln!();
+                        return Ok(Some(amount));
+                    }
+                    // no cursor moves in plain mode, so start over on a new line
+                    Err(e) if linear() => {
+                        emitln!(" {e}");
+                        text.clear();
+                    }
+                    Err(e) => {
+                        emit!("\x1B[s \x1B[31m{e}\x1B[0m\x1B[u");
+                        complaint = true;
+                    }
+                },
+                '\x1B' => {
+                    emitln!();
+                    return Ok(None);
+                }
+                '\x08' | '\x7F' if text.pop().is_some() => put("\x08 \x08"),
+                c if (c.is_ascii_digit() || "kmaxe.,".contains(c.to_ascii_lowercase()))
+                    && text.len() < 16 =>
+                {
+                    text.push(c);
+                    emit!("{c}");
+                }
+                _ => {}
+            }
+            io::stdout().flush()?;
         }
     }
 
-    fn term_info(&mut self) {
-        if let Some((Width(w), Height(h))) = terminal_size() {
-            self.width = w as usize;
-            self.height = h as usize;
-            self.wid = self.width / 2;
+    fn line(&mut self) -> io::Result<String> {
+        let mut reply = String::new();
+        if io::stdin().read_line(&mut reply)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
         }
+        Ok(reply.trim().to_string())
     }
 
-    fn format_number(n: i64) -> String {
-        let num = n.abs();
-        let sign = if n < 0 { "-" } else { "" };
-        let s = num.to_string();
-        let mut result = String::new();
-        let chars: Vec<char> = s.chars().rev().collect();
-        for (i, c) in chars.iter().enumerate() {
-            if i != 0 && i % 3 == 0 {
-                result.push(',');
+    fn key(&mut self) -> io::Result<char> {
+        #[cfg(unix)]
+        {
+            use nix::errno::Errno;
+            use nix::libc::STDIN_FILENO;
+            use nix::unistd::read;
+
+            // straight from the fd, past std's buffer, so pending() can see
+            // what's left of an escape sequence
+            let raw = term::RawMode::enter()?;
+            let mut buffer = [0; 1];
+            let res = loop {
+                if RESIZED.swap(false, AtomicOrdering::Relaxed) {
+                    break Err(io::Error::other(Resized));
+                }
+                match read(STDIN_FILENO, &mut buffer) {
+                    Ok(0) => break Err(io::ErrorKind::UnexpectedEof.into()),
+                    Ok(_) if buffer[0] == 0x1B && term::pending(30) => {
+                        let _ = read(STDIN_FILENO, &mut [0; 16]);
+                    }
+                    Ok(_) => break Ok(()),
+                    Err(Errno::EINTR) => continue,
+                    Err(e) => break Err(e.into()),
+                }
+            };
+            drop(raw);
+
+            match res {
+                Ok(()) => {
+                    if buffer[0] == 3 {
+                        Err(io::ErrorKind::Interrupted.into())
+                    } else {
+                        Ok(buffer[0] as char)
+                    }
+                }
+                Err(e) => Err(e),
             }
-            result.push(*c);
         }
-        result.push_str(sign);
-        result.chars().rev().collect()
-    }
 
-    fn game_date_str(&self, days_to_add: i64) -> String {
-        let start_date = NaiveDate::from_ymd_opt(1983, 12, 4).unwrap();
-        let game_date = start_date + chrono::Duration::days(days_to_add);
-        format!(
-            "{:02} / {:02} / {:02}",
-            game_date.month(),
-            game_date.day(),
-            game_date.year() % 100
-        )
-    }
+        #[cfg(windows)]
+        #[allow(unused_imports)]
+        {
+            use winapi::um::wincon::{PeekConsoleInputW, INPUT_RECORD, KEY_EVENT, KEY_EVENT_RECORD, WINDOW_BUFFER_SIZE_EVENT};
+            use winapi::um::consoleapi::{ReadConsoleInputW, SetConsoleCtrlHandler};
+            use winapi::um::winbase::STD_INPUT_HANDLE;
+            use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+            use winapi::um::processenv::GetStdHandle;
+            use winapi::shared::minwindef::DWORD;
+            use std::sync::atomic::{AtomicBool, Ordering};
+            use std::{io, process};
+            use std::ptr::null_mut;
 
-    fn start_game(&mut self) {
-        self.term_info();
-        clear_screen();
-        println!("\n\n");
-        println!(
-            "{}\x1B[1;32mDRUG WARS\x1B[0m",
-            " ".repeat(self.wid - 5)
-        );
-        println!();
-        println!(
-            "{}\x1B[1;32mA GAME BASED ON\x1B[0m",
-            " ".repeat(self.wid - 8)
-        );
-        println!();
-        println!(
-            "{}\x1B[1;32mTHE NEW YORK DRUG MARKET\x1B[0m",
-            " ".repeat(self.wid - 12)
-        );
-        println!("\n\n\n\n");
-        println!(
-            "{}ORIGINAL BY JOHN E. DELL (1984)",
-            " ".repeat(self.wid - 15)
-        );
-        println!();
-        println!(
-            "{}\x1B[35mRUST BY URRICK HUNT (2024)\x1B[0m",
-            " ".repeat(self.wid - 13)
-        );
-        println!("\n\n\n\n");
+            static CTRL_C_HANDLED: AtomicBool = AtomicBool::new(false);
 
-        print!(
-            "{}\x1B[33mDO YOU WANT INSTRUCTIONS?\x1B[0m ",
-            " ".repeat(self.wid - 13)
-        );
-        io::stdout().flush().unwrap();
-        loop {
-            let reply = self.getch().unwrap().to_lowercase().next().unwrap();
+            unsafe extern "system" fn ctrl_handler(_: u32) -> i32 {
+                CTRL_C_HANDLED.store(true, Ordering::SeqCst);
+                1 
+            }
 
-            if reply == 'y' {
-                self.instructions();
-                break;
-            } else if reply == 'n' {
-                self.roll_event();
-                self.main_menu();
-                break;
+            unsafe {
+                SetConsoleCtrlHandler(Some(ctrl_handler), 1);
             }
-        }
-    }
 
-    fn instructions(&mut self) {
-        self.term_info();
-        clear_screen();
-        println!("\n\n");
-        println!(
-            "{}\x1B[1;32mDRUG WARS\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}This is a game of buying, selling, and",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}fighting. The object of the game is to",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}pay off your debt to the loan shark.",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}Then, make as much money as you can in a",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}1 month period. If you deal too heavily",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}in  drugs,  you  might  run  into  the",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}police !!  Your main drug stash will be",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}in the Bronx. (It's a nice neighborhood)",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}The prices of drugs per unit are:",
-            " ".repeat(self.wid - 20)
-        );
-        println!("{}", " ".repeat(self.wid - 20));
-        println!("{}", " ".repeat(self.wid - 20));
-        println!(
-            "{}      \x1B[35mCOCAINE     15000-30000\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}      \x1B[35mHEROIN      5000-14000\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}      \x1B[35mACID        1000-4500\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}      \x1B[35mWEED        300-900\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}      \x1B[35mSPEED       70-250\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!(
-            "{}      \x1B[35mLUDES       10-60\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        println!("{}", " ".repeat(self.wid - 20));
-        println!("{}", " ".repeat(self.wid - 20));
-        println!("{}", " ".repeat(self.wid - 20));
-        print!(
-            "{}    \x1B[33m(HIT ANY KEY TO START GAME)\x1B[0m",
-            " ".repeat(self.wid - 20)
-        );
-        io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-        self.roll_event();
-        self.main_menu();
-    }
+            unsafe {
+                let handle = GetStdHandle(STD_INPUT_HANDLE);
+                if handle == INVALID_HANDLE_VALUE {
+                    return Err(io::Error::last_os_error());
+                }
+                let _raw = term::RawMode::enter()?;
 
-    fn hud(&mut self) {
-        self.term_info();
-        clear_screen();
+                let mut input_record: INPUT_RECORD = std::mem::zeroed();
+                let mut events_read = 0;
 
-        let game_date = self.game_date_str(self.day);
-        let bank_formatted = Self::format_number(self.bank);
-        let debt_formatted = Self::format_number(self.debt);
-        let cash_formatted = Self::format_number(self.cash);
+                loop {
+                    if CTRL_C_HANDLED.swap(false, Ordering::SeqCst) {
+                        return Err(io::ErrorKind::Interrupted.into());
+                    }
 
-        println!("\n");
-        println!(
-            "DATE {}                        HOLD  \x1B[33m{}\x1B[0m",
-            game_date, self.hold
-        );
-        println!(
-            "DAYS LEFT      \x1B[35m{:02}\x1B[0m                        \x1B[34m{}\x1B[0m\n",
-            self.days_left, self.geo
-        );
+                    let peek_result = PeekConsoleInputW(handle, &mut input_record, 1, &mut events_read);
+                    if peek_result == 0 || events_read == 0 {
+                        continue; 
+                    }
 
-        println!("  ┌─────────────────────────┬─────────────────────────┐");
-        println!("  │        STASH            │       TRENCH COAT       │");
-        println!("  ├─────────────────────────┼─────────────────────────┤");
-        for drug in [
-            Drug::Cocaine,
-            Drug::Heroin,
-            Drug::Acid,
-            Drug::Weed,
-            Drug::Speed,
-            Drug::Ludes,
-        ]
-        .iter()
-        {
-            let stash_amount = self.stash.get(drug).unwrap_or(&0);
-            let trench_amount = self.trench_coat.get(drug).unwrap_or(&0);
-            println!(
-                "  │ {:<9}  {:<6}       │ {:<9}  {:<6}       │",
-                drug.as_str(),
-                stash_amount,
-                drug.as_str(),
-                trench_amount
-            );
-        }
-        println!("  │                         │                         │");
-        println!(
-            "  │ BANK       \x1B[36m{:<12}\x1B[0m │ GUNS       {:<6}       │",
-            bank_formatted, self.guns
-        );
-        println!(
-            "  │ DEBT       \x1B[35m{:<8}\x1B[0m     │ CASH       \x1B[32m{:<12}\x1B[0m │",
-            debt_formatted, cash_formatted
-        );
-        println!("  └─────────────────────────┴─────────────────────────┘\n");
-    }
+                    if ReadConsoleInputW(handle, &mut input_record, 1, &mut events_read) == 0 {
+                        return Err(io::Error::last_os_error());
+                    }
 
-    fn show_prices(&self) {
-        println!("HEY DUDE, THE PRICES OF DRUGS HERE ARE:");
-        println!();
-        println!(
-            "    COCAINE    {:<11}    WEED       {:<11}",
-            Self::format_number(*self.prices.get(&Drug::Cocaine).unwrap()),
-            Self::format_number(*self.prices.get(&Drug::Weed).unwrap())
-        );
-        println!(
-            "    HEROIN     {:<11}    SPEED      {:<11}",
-            Self::format_number(*self.prices.get(&Drug::Heroin).unwrap()),
-            Self::format_number(*self.prices.get(&Drug::Speed).unwrap())
-        );
-        println!(
-            "    ACID       {:<11}    LUDES      {:<11}",
-            Self::format_number(*self.prices.get(&Drug::Acid).unwrap()),
-            Self::format_number(*self.prices.get(&Drug::Ludes).unwrap())
-        );
-        println!();
-    }
+                    if input_record.EventType == WINDOW_BUFFER_SIZE_EVENT {
+                        return Err(io::Error::other(Resized));
+                    }
 
-    fn yn_prompt<F1, F2>(&mut self, prompt: &str, yes_action: F1, no_action: F2)
-    where
-        F1: FnOnce(&mut GameState),
-        F2: FnOnce(&mut GameState),
-    {
-        print!("{prompt}");
-        io::stdout().flush().unwrap();
-        loop {
-            let reply = self.getch().unwrap();
-            match reply {
-                'y' | 'Y' => {
-                    yes_action(self);
-                    break;
-                }
-                'n' | 'N' => {
-                    no_action(self);
-                    break;
-                }
-                _ => {
-                    continue;
+                    if input_record.EventType == KEY_EVENT && input_record.Event.KeyEvent().bKeyDown != 0 {
+                        let key_event: KEY_EVENT_RECORD = *input_record.Event.KeyEvent();
+                        #[allow(clippy::cast_possible_truncation)]
+                        let char_code = *key_event.uChar.UnicodeChar() as u8 as char;
+
+                        if char_code == '\u{3}' {
+                            return Err(io::ErrorKind::Interrupted.into());
+                        }
+
+                        return Ok(char_code);
+                    }
                 }
             }
         }
     }
+}
 
-    fn loan(&mut self) {
-        self.hud();
-        self.yn_prompt(
-            "DO YOU WANT TO VISIT THE LOAN SHARK? ",
-            GameState::repay,
-            GameState::stash,
-        );
-    }
+// plays back a journal, once it runs out or stops matching what the
+// game asks for, the keyboard takes over
+struct Replay {
+    inputs: VecDeque<Input>,
+    step: bool,
+    terminal: Terminal,
+}
 
-    fn repay(&mut self) {
-        self.hud();
-        print!("HOW MUCH TO REPAY? ");
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
-        if amount == 0 {
-            self.borrow();
-        } else if amount > self.cash || amount > self.debt {
-            self.repay();
-        } else {
-            self.cash -= amount;
-            self.debt -= amount;
-            self.borrow();
+impl Replay {
+    // a step waits for a key before the input is taken, so a ctrl-c there
+    // pauses without losing it
+    fn next(&mut self) -> io::Result<Option<Input>> {
+        if !self.inputs.is_empty() && self.step {
+            self.terminal.key()?;
         }
+        Ok(self.inputs.pop_front())
     }
+}
 
-    fn borrow(&mut self) {
-        self.hud();
-        print!("HOW MUCH TO BORROW? ");
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
-        if amount == 0 {
-            self.stash();
-        } else if (amount + self.debt) <= self.maxloan {
-            self.debt += amount;
-            self.cash += amount;
-            self.stash();
-        } else {
-            println!("YOU THINK HE IS CRAZY MAN !!!");
-            thread::sleep(Duration::from_secs(1));
-            self.borrow();
+impl InputSource for Replay {
+    fn key(&mut self) -> io::Result<char> {
+        match self.next()? {
+            Some(Input::Key(c)) => Ok(c),
+            Some(_) => {
+                self.inputs.clear();
+                self.terminal.key()
+            }
+            None => self.terminal.key(),
         }
     }
 
-    fn stash(&mut self) {
-        self.hud();
-        self.yn_prompt(
-            "DO YOU WISH TO TRANSFER DRUGS TO YOUR STASH? ",
-            GameState::stashing,
-            GameState::banking,
-        );
+    // the journal keeps the amount it came to, so it isn't checked again
+    fn amount(&mut self, text: &mut String, check: Check) -> io::Result<Option<i64>> {
+        match self.next()? {
+            Some(Input::Number(n)) => {
+                emitln!("{n}");
+                Ok(Some(n))
+            }
+            Some(_) => {
+                self.inputs.clear();
+                self.terminal.amount(text, check)
+            }
+            None => self.terminal.amount(text, check),
+        }
     }
 
-    fn stashing(&mut self) {
-        self.hud();
-        print!("WHICH DRUG DO YOU WANT TO STASH OR TAKE? ");
-        io::stdout().flush().unwrap();
-        let reply = self.getch().unwrap().to_lowercase().next().unwrap();
-        println!("{reply}");
-        if let Some(drug) = GameState::get_drug_from_char(reply) {
-            self.stash_deposit(drug);
-        } else {
-            self.stash();
+    fn line(&mut self) -> io::Result<String> {
+        match self.next()? {
+            Some(Input::Text(text)) => {
+                emitln!("{text}");
+                Ok(text)
+            }
+            Some(_) => {
+                self.inputs.clear();
+                self.terminal.line()
+            }
+            None => self.terminal.line(),
         }
     }
 
-    fn stash_deposit(&mut self, drug: Drug) {
-        self.hud();
-        print!("HOW MUCH {} DO YOU WANT TO STASH? ", drug.as_str());
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
+    // the journal holds the game's inputs, not a pause's, so a pause during
+    // a replay is answered at the keyboard & the journal stays in step
+    fn live_key(&mut self) -> io::Result<char> {
+        self.terminal.key()
+    }
+}
 
-        if amount != 0 {
-            let trench_amount_value = *self.trench_coat.get(&drug).unwrap_or(&0);
-            if amount > 0 && amount <= trench_amount_value {
-                if let Some(trench_amount) = self.trench_coat.get_mut(&drug) {
-                    *trench_amount -= amount;
-                }
-                if let Some(stash_amount) = self.stash.get_mut(&drug) {
-                    *stash_amount += amount;
-                }
-                self.hold += amount;
-            } else {
-                self.stash();
-                return;
-            }
-        }
+// whitespace separated tokens from a pipe or a script file, # starts a
+// comment. a key takes one character, so `nnn` answers three prompts,
+// and a number or a name takes a whole token
+struct Script<R> {
+    reader: R,
+    tokens: VecDeque<String>,
+}
 
-        self.hud();
-        print!("HOW MUCH {} DO YOU WANT TO TAKE? ", drug.as_str());
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
+impl<R: BufRead> Script<R> {
+    fn new(reader: R) -> Self {
+        Script {
+            reader,
+            tokens: VecDeque::new(),
+        }
+    }
 
-        if amount != 0 {
-            let stash_amount_value = *self.stash.get(&drug).unwrap_or(&0);
-            if amount > 0 && amount <= stash_amount_value && self.hold - amount >= 0 {
-                if let Some(stash_amount) = self.stash.get_mut(&drug) {
-                    *stash_amount -= amount;
-                }
-                if let Some(trench_amount) = self.trench_coat.get_mut(&drug) {
-                    *trench_amount += amount;
-                }
-                self.hold -= amount;
-            } else {
-                self.stash();
-                return;
+    fn token(&mut self) -> io::Result<String> {
+        while self.tokens.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "ran out of input",
+                ));
             }
+            let line = line.split('#').next().unwrap_or("");
+            self.tokens
+                .extend(line.split_whitespace().map(str::to_string));
         }
-
-        self.banking();
+        Ok(self.tokens.pop_front().unwrap())
     }
+}
 
-    fn banking(&mut self) {
-        self.hud();
-        self.yn_prompt(
-            "DO YOU WISH TO VISIT THE BANK? ",
-            GameState::visit_bank,
-            GameState::main_menu,
-        );
+impl<R: BufRead> InputSource for Script<R> {
+    fn key(&mut self) -> io::Result<char> {
+        let token = self.token()?;
+        let mut chars = token.chars();
+        let c = chars.next().unwrap();
+        let rest: String = chars.collect();
+        if !rest.is_empty() {
+            self.tokens.push_front(rest);
+        }
+        Ok(c)
     }
 
-    fn visit_bank(&mut self) {
-        self.hud();
-        print!("HOW MUCH TO DEPOSIT? ");
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
-        if amount > 0 && amount <= self.cash {
-            self.bank += amount;
-            self.cash -= amount;
-        }
-        self.hud();
-        print!("HOW MUCH TO WITHDRAW? ");
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
-        if amount > 0 && amount <= self.bank {
-            self.bank -= amount;
-            self.cash += amount;
-        }
-        self.main_menu();
-    }
-
-    fn main_menu(&mut self) {
-        self.hud();
-        self.show_prices();
-        if self.state == "begin" {
-            self.state = "normal".to_string();
-            if self.day == 0 {
-                self.loan();
+    // a bad amount gives up on the prompt rather than eat the next answer
+    fn amount(&mut self, _text: &mut String, check: Check) -> io::Result<Option<i64>> {
+        let token = self.token()?;
+        match check(&token) {
+            Ok(n) => {
+                emitln!("{n}");
+                Ok(Some(n))
             }
-        } else if self.state == "BRONXDO" {
-            self.state = "normal".to_string();
-            self.loan();
-        } else if self.state == "normal" {
-            self.buy_sell_jet();
-        }
-    }
-
-    fn buy_sell_jet(&mut self) {
-        print!("WILL YOU BUY, SELL OR JET? ");
-        io::stdout().flush().unwrap();
-        loop {
-            let reply = self.getch().unwrap();
-            match reply {
-                'b' | 'B' => {
-                    println!();
-                    self.buying();
-                    break;
-                }
-                's' | 'S' => {
-                    println!();
-                    self.selling();
-                    break;
-                }
-                'j' | 'J' => {
-                    println!();
-                    self.jet();
-                    break;
-                }
-                _ => {
-                    continue;
-                }
+            Err(e) => {
+                emitln!("{token} {e}");
+                Ok(None)
             }
         }
     }
 
-    fn buying(&mut self) {
-        print!("WHAT WILL YOU BUY? ");
-        io::stdout().flush().unwrap();
-        let reply = self.getch().unwrap().to_lowercase().next().unwrap();
-        println!("{reply}");
-        if let Some(drug) = GameState::get_drug_from_char(reply) {
-            self.buy_drug(drug);
-        } else {
-            self.main_menu();
-        }
+    fn line(&mut self) -> io::Result<String> {
+        let text = self.token()?;
+        emitln!("{text}");
+        Ok(text)
     }
+}
 
-    fn buy_drug(&mut self, drug: Drug) {
-        self.hud();
-        self.show_prices();
+struct Client {
+    game: Game,
+    // where to go once the day's events, offers and fights are settled
+    arrival: Phase,
+    save_path: Option<PathBuf>,
+    input: Box<dyn InputSource>,
+    journal: Option<JournalWriter>,
+    keep_score: bool,
+    // the profile this game counts towards, picked by name
+    player: Option<String>,
+    // the last achievement unlocked, shown on the hud until the next jet
+    banner: Option<Achievement>,
+    // in a game, where esc, q & ctrl-c pause it
+    playing: bool,
+    // the hud as a screen reader last heard it
+    read_out: String,
+    width: usize,
+    height: usize,
+    wid: usize,
+}
 
-        let price = *self.prices.get(&drug).unwrap_or(&0);
-        let afford = if price > 0 {
-            self.cash / price
-        } else {
-            0
+impl Client {
+    fn new(game: Game, save_path: Option<PathBuf>, input: Box<dyn InputSource>) -> Self {
+        let mut client = Client {
+            game,
+            arrival: Phase::Market,
+            save_path,
+            input,
+            journal: None,
+            keep_score: true,
+            player: None,
+            banner: None,
+            playing: false,
+            read_out: String::new(),
+            width: 80,
+            height: 24,
+            wid: 40,
         };
 
-        println!("YOU CAN AFFORD ( {afford} )");
-        print!("HOW MUCH {} DO YOU WANT TO BUY? ", drug.as_str());
-        io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
+        client.term_info();
+        client
+    }
 
-        if amount == 0 {
-            self.main_menu();
-        } else if amount > 0 && amount <= afford && (self.hold - amount) >= 0 {
-            if let Some(entry) = self.trench_coat.get_mut(&drug) {
-                *entry += amount;
+    // waits on the too small screen until the window fits
+    fn term_info(&mut self) {
+        while let Some((Width(w), Height(h))) = terminal_size() {
+            self.width = w as usize;
+            self.height = h as usize;
+            self.wid = self.width / 2;
+            if self.fits() || !io::stdin().is_terminal() || READER.load(AtomicOrdering::Relaxed) {
+                break;
             }
-            self.cash -= amount * price;
-            self.hold -= amount;
-            self.main_menu();
-        } else {
-            self.main_menu();
+            self.too_small();
         }
     }
 
-    fn selling(&mut self) {
-        print!("WHAT WILL YOU SELL? ");
-        io::stdout().flush().unwrap();
-        let reply = self.getch().unwrap().to_lowercase().next().unwrap();
-        println!("{reply}");
-        if let Some(drug) = GameState::get_drug_from_char(reply) {
-            self.sell_drug(drug);
-        } else {
-            self.main_menu();
-        }
+    // room for the hud & a prompt under it
+    fn fits(&self) -> bool {
+        self.width >= MIN_WIDTH && self.height >= self.min_height()
     }
 
-    fn sell_drug(&mut self, drug: Drug) {
-        self.hud();
-        self.show_prices();
-
-        let price = *self.prices.get(&drug).unwrap_or(&0);
-        let trench_amount_value = *self.trench_coat.get(&drug).unwrap_or(&0);
+    fn min_height(&self) -> usize {
+        self.game.catalog().len() + 14
+    }
 
-        println!("YOU CAN SELL ( {trench_amount_value} )");
-        print!("HOW MUCH {} DO YOU WANT TO SELL? ", drug.as_str());
+    // keys are ignored until a resize, ctrl-c still ends the game
+    fn too_small(&mut self) {
+        clear_screen();
+        emitln!("\x1B[31m{}\x1B[0m", t!("too_small"));
+        emitln!();
+        emitln!("{}", t!("need", width = MIN_WIDTH, height = self.min_height()));
+        emitln!("{}", t!("have", width = self.width, height = self.height));
+        emitln!();
+        emit!("\x1B[33m{}\x1B[0m", t!("make_bigger"));
         io::stdout().flush().unwrap();
-        let amount = self.read_number_input();
-
-        if amount == 0 {
-            self.main_menu();
-        } else if amount > 0 && amount <= trench_amount_value {
-            if let Some(trench_amount) = self.trench_coat.get_mut(&drug) {
-                *trench_amount -= amount;
+        loop {
+            match Terminal.key() {
+                Err(e) if is_resize(&e) => return,
+                Err(e) => self.input_failed(e),
+                Ok(_) => {}
             }
-            self.cash += amount * price;
-            self.hold += amount;
-            self.main_menu();
-        } else {
-            self.main_menu();
         }
     }
 
-    fn jet(&mut self) {
-        self.hud();
-        println!();
-        println!("   1) BRONX        2) GHETTO          3) CENTRAL PARK");
-        println!("   4) MANHATTAN    5) CONEY ISLAND    6) BROOKLYN");
-        println!();
-        print!("WHERE TO DUDE: ");
-        io::stdout().flush().unwrap();
-        let reply = self.getch().unwrap();
-        println!("{reply}");
-        match reply {
-            '1' => {
-                self.geo = "BRONX".to_string();
-                self.state = "BRONXDO".to_string();
-                self.new_day();
-            }
-            '2' => {
-                self.geo = "GHETTO".to_string();
-                self.new_day();
-            }
-            '3' => {
-                self.geo = "CENTRAL PARK".to_string();
-                self.new_day();
-            }
-            '4' => {
-                self.geo = "MANHATTAN".to_string();
-                self.new_day();
-            }
-            '5' => {
-                self.geo = "CONEY ISLAND".to_string();
-                self.new_day();
-            }
-            '6' => {
-                self.geo = "BROOKLYN".to_string();
-                self.new_day();
-            }
-            _ => self.main_menu(),
-        }
+    // spaces to center something 2n wide, none when the window is narrower
+    fn pad(&self, n: usize) -> String {
+        " ".repeat(self.wid.saturating_sub(n))
     }
 
-    fn new_day(&mut self) {
-        self.hud();
-        self.days_left -= 1;
-        if self.days_left <= 0 {
-            self.you_win();
-        } else {
-            self.day += 1;
-            self.roll_prices();
-            self.debt = self.debt * 110 / 100;
-            self.bank = self.bank * 105 / 100;
-            self.roll_event();
-            self.roll_fight();
-            self.main_menu();
-        }
+    // the same for a line of text, whatever language it's in
+    fn center(&self, text: &str) -> String {
+        self.pad(text.chars().count().div_ceil(2))
     }
 
-    fn you_win(&self) {
-        clear_screen();
-        let total_money = self.bank + self.cash - self.debt;
-        let total_money_display = Self::format_number(total_money);
+    fn centered(&self, key: &str, color: &str) {
+        let text = t!(key);
+        emitln!("{}{}{}\x1B[0m", self.center(text), color, text);
+    }
 
-        let score = if total_money > 50_000_000 {
-            100
-        } else if total_money >= 25_000_000 {
-            99
-        } else if total_money >= 10_000_000 {
-            98
-        } else {
-            let calculated_score = (total_money * 100) / 10_000_000;
-            calculated_score.clamp(0, 97)
-        };
+    // paints a screen that depends on the window size, so a resize can
+    // paint it again & put back whatever was printed under it
+    fn paint(&mut self, painter: fn(&mut Client)) {
+        painter(self);
+        let mut screen = SCREEN.lock().unwrap();
+        screen.painter = Some(painter);
+        screen.painted = screen.text.len();
+    }
 
-        println!(
-            "\x1B[38;2;255;202;128mGAME OVER\x1B[0m\nYOU SURVIVED FOR \x1B[33m{}\x1B[0m DAYS!",
-            self.day
-        );
-        println!("YOUR TOTAL MONEY: \x1B[32m{}\x1B[0m", total_money_display);
-        println!("YOUR SCORE: \x1B[35m{}\x1B[0m OUT OF 100", score);
-
-        let dealer_rank = if score == 100 {
-            "GANGSTA MOTHERFUCKER"
-        } else if score == 99 {
-            "HUSTLER FUCKER"
-        } else if score == 98 {
-            "PABLO ESCOBAR"
-        } else if (76..=97).contains(&score) {
-            "KINGPIN"
-        } else if (51..=75).contains(&score) {
-            "RUN THE TOWN"
-        } else if (31..=50).contains(&score) {
-            "OWN THE BLOCK"
-        } else {
-            "SMALL TIME PUSHA"
+    fn redraw(&mut self) {
+        let (text, painter, painted) = {
+            let screen = SCREEN.lock().unwrap();
+            (screen.text.clone(), screen.painter, screen.painted)
         };
-
-        match score {
-            100 => println!("DEALER RANK: \x1B[36m{} ... YOU ARE MY HERO\x1B[0m", dealer_rank),
-            99 => println!("DEALER RANK: \x1B[36m{} ... YOU THA DOPE MAN\x1B[0m", dealer_rank),
-            98 => println!("DEALER RANK: \x1B[36m{} ... YOU ARE A GOD\x1B[0m", dealer_rank),
-            76..=97 => println!("DEALER RANK: \x1B[36m{} ... GOD DAMN\x1B[0m", dealer_rank),
-            51..=75 => println!("DEALER RANK: \x1B[36m{} ... PRETTY GOOD\x1B[0m", dealer_rank),
-            31..=50 => println!("DEALER RANK: \x1B[36m{} ... NOT BAD\x1B[0m", dealer_rank),
-            _ => println!("DEALER RANK: \x1B[36m{} ... WEAK\x1B[0m", dealer_rank),
+        // a screen reader only needs to hear the prompt again
+        if READER.load(AtomicOrdering::Relaxed) {
+            put(&text[painted..]);
+            io::stdout().flush().unwrap();
+            return;
         }
+        match painter {
+            Some(painter) => {
+                self.paint(painter);
+                put(&text[painted..]);
+            }
+            None => {
+                self.term_info();
+                clear_screen();
+                put(&text);
+            }
+        }
+        io::stdout().flush().unwrap();
+    }
 
-        let now = Local::now();
-        let date = format!("{:02} / {:02} / {:02}", now.month(), now.day(), now.year() % 100);
+    fn format_number(n: i64) -> String {
+        locale().number(n)
+    }
 
-        let new_score = HighScore {
-            total_money,
-            dealer_rank: dealer_rank.to_string(),
-            date,
-        };
+    fn game_date_str(days_to_add: i64) -> String {
+        let start_date = NaiveDate::from_ymd_opt(1983, 12, 4).unwrap();
+        let game_date = start_date + chrono::Duration::days(days_to_add);
+        t!(
+            "date_format",
+            month = format!("{:02}", game_date.month()),
+            day = format!("{:02}", game_date.day()),
+            year = format!("{:02}", game_date.year() % 100)
+        )
+    }
+
+    // a difficulty the way the screens show it
+    fn difficulty_name(difficulty: Difficulty) -> String {
+        locale().name(&difficulty.as_str().to_uppercase()).to_string()
+    }
 
-        let mut high_scores: Vec<HighScore> = Vec::new();
-
-        if let Ok(file) = File::open("highscores.txt") {
-            let reader = BufReader::new(file);
-            for line in reader.lines().map_while(Result::ok) {
-                let parts: Vec<&str> = line.trim().split(',').collect();
-                if parts.len() == 3 {
-                    if let Ok(total_money) = parts[0].parse::<i64>() {
-                        let dealer_rank = parts[1].to_string();
-                        let date = parts[2].to_string();
-                        high_scores.push(HighScore {
-                            total_money,
-                            dealer_rank,
-                            date,
-                        });
+    fn start_game(&mut self) -> Phase {
+        self.paint(Self::title);
+
+        // a replay never has a save to continue, so the answer stays out of
+        // the journal
+        if let Some(path) = self.save_path.clone().filter(|path| path.exists()) {
+            let journal = self.journal.take();
+            let prompt = t!("continue_saved");
+            emit!("{}\x1B[33m{}\x1B[0m ", self.center(prompt), prompt);
+            if self.yn_prompt("") {
+                match load_game(&path) {
+                    Ok(game) => {
+                        self.game = game;
+                        return Phase::Market;
+                    }
+                    Err(e) => {
+                        emit!("\r\x1B[2K\x1B[31m{}\x1B[0m ", t!("cant_load", error = e));
+                        io::stdout().flush().unwrap();
+                        self.wait_for_key_press();
                     }
                 }
             }
+            emit!("\r\x1B[2K");
+            self.journal = journal;
         }
 
-        high_scores.push(new_score);
+        let menu = t!("title_menu", scores = hotkey("scores"), profiles = hotkey("profiles"));
+        emitln!("{}\x1B[36m{}\x1B[0m", self.center(&menu), menu);
+        let prompt = t!("want_instructions");
+        emit!("{}\x1B[33m{}\x1B[0m ", self.center(prompt), prompt);
+        io::stdout().flush().unwrap();
+        loop {
+            let reply = self.getch().to_lowercase().next().unwrap();
+
+            if reply == key("yes") {
+                return Phase::Instructions;
+            } else if reply == key("no") {
+                return Phase::Begin;
+            } else if reply == key("scores") {
+                return Phase::Scores(None, SortBy::Money);
+            } else if reply == key("profiles") {
+                return Phase::Profiles(0);
+            }
+        }
+    }
 
-        high_scores.sort();
+    fn title(&mut self) {
+        self.term_info();
+        clear_screen();
+        emitln!("\n\n");
+        self.centered("drug_wars", "\x1B[1;32m");
+        emitln!();
+        self.centered("based_on", "\x1B[1;32m");
+        emitln!();
+        self.centered("ny_drug_market", "\x1B[1;32m");
+        emitln!("\n\n\n\n");
+        self.centered("original_by", "");
+        emitln!();
+        self.centered("rust_by", "\x1B[35m");
+        emitln!("\n\n\n\n");
+    }
 
-        if high_scores.len() > 10 {
-            high_scores.truncate(10);
-        }
+    fn instructions(&mut self) -> Phase {
+        self.paint(Self::instructions_screen);
+        self.wait_for_key_press();
+        Phase::Begin
+    }
 
-        if let Ok(mut file) = File::create("highscores.txt") {
-            for hs in &high_scores {
-                writeln!(file, "{},{},{}", hs.total_money, hs.dealer_rank, hs.date).unwrap();
-            }
+    fn instructions_screen(&mut self) {
+        self.term_info();
+        clear_screen();
+        emitln!("\n\n");
+        emitln!("{}\x1B[1;32m{}\x1B[0m", self.pad(20), t!("drug_wars"));
+        for line in t!("instructions").lines() {
+            emitln!("{}{}", self.pad(20), line);
         }
-
-        println!("\x1B[38;2;255;202;128m\nTOP 10 HIGH SCORES\n\x1B[0m");
-        for (i, hs) in high_scores.iter().enumerate() {
-            println!(
-                "{:02}  \x1B[32m{}\x1B[0m  \x1B[36m{}\x1B[0m  \x1B[35m{}\x1B[0m",
-                i + 1,
-                Self::format_number(hs.total_money),
-                hs.dealer_rank,
-                hs.date
+        emitln!("{}", self.pad(20));
+        emitln!("{}", self.pad(20));
+        for drug in self.game.catalog().drugs() {
+            let spec = self.game.catalog().spec(drug);
+            emitln!(
+                "{}      {}{:<12}{}-{}\x1B[0m",
+                self.pad(20),
+                color_code(&spec.color),
+                locale().name(&spec.name),
+                spec.min_price,
+                spec.max_price
             );
         }
+        emitln!("{}", self.pad(20));
+        emitln!("{}", self.pad(20));
+        emitln!("{}", self.pad(20));
+        emit!("{}    \x1B[33m{}\x1B[0m", self.pad(20), t!("start_game"));
+        io::stdout().flush().unwrap();
+    }
 
-        process::exit(0);
+    fn begin(&mut self) -> Phase {
+        self.arrival = Phase::Loan;
+        let events = self.game.start();
+        self.play(events)
     }
 
-    fn roll_fight(&mut self) {
-        let mut rng = rand::thread_rng();
-        let fight_chance = rng.gen_range(1..=100) / (self.hold + 1);
-        if fight_chance >= 1 {
-            self.cops = fight_chance / 9 + 2;
-            self.hud();
-            print!(
-                "\x1B[31mOFFICER HARDASS AND {} OF HIS DEPUTIES ARE CHASING YOU !!!!!\x1B[0m",
-                self.cops
-            );
-            io::stdout().flush().unwrap();
-            self.wait_for_key_press();
-            self.fight();
-        }
+    fn hud(&mut self) {
+        self.paint(Self::draw_hud);
     }
 
-    fn fight_hud(&mut self) {
+    // the top of the hud, the date & where you are
+    fn draw_day(&mut self) {
         self.term_info();
         clear_screen();
-        println!();
-        let bar = "█".repeat(self.width);
-        println!("\x1B[35m{}\x1B[0m", bar);
-        println!();
-        println!(
-            "{}DAMAGE    \x1B[35m{}\x1B[0m       COPS    \x1B[36m{}\x1B[0m       GUNS    \x1B[34m{}\x1B[0m",
-            " ".repeat(self.width / 8),
-            self.damage,
-            self.cops,
-            self.guns
+
+        let game_date = Self::game_date_str(self.game.day());
+        match self.banner {
+            Some(achievement) => emitln!(
+                "\x1B[1;33m{}\x1B[0m\n",
+                t!("achievement_unlocked", name = locale().name(achievement.as_str()))
+            ),
+            None => emitln!("\n"),
+        }
+        if READER.load(AtomicOrdering::Relaxed) {
+            self.read_out_hud();
+            return;
+        }
+        // the hold & the place line up 41 columns in
+        emitln!(
+            "{:<41}{}  \x1B[33m{}\x1B[0m",
+            format!("{} {}", t!("date"), game_date),
+            t!("hold"),
+            self.game.hold()
+        );
+        let label = format!("{:<14} ", t!("days_left"));
+        let days_left = format!("{:02}", self.game.days_left());
+        let gap = 41usize.saturating_sub(label.chars().count() + days_left.len());
+        emitln!(
+            "{}\x1B[35m{}\x1B[0m{}\x1B[34m{}\x1B[0m\n",
+            label,
+            days_left,
+            " ".repeat(gap.max(1)),
+            self.place_name(self.game.location())
         );
-        println!();
-        println!("\x1B[35m{}\x1B[0m", bar);
-        println!("\n\n");
     }
 
-    fn fight(&mut self) {
-        self.term_info();
-        self.fight_hud();
+    fn draw_hud(&mut self) {
+        self.draw_day();
+        if READER.load(AtomicOrdering::Relaxed) {
+            return;
+        }
 
-        if self.damage >= 50 {
-            print!("\x1B[31mTHEY WASTED YOU MAN !! WHAT A DRAG !!!\x1B[0m ");
-            io::stdout().flush().unwrap();
-            self.wait_for_key_press();
-            self.you_win();
+        let bank_formatted = Self::format_number(self.game.bank());
+        let debt_formatted = Self::format_number(self.game.debt());
+        let cash_formatted = Self::format_number(self.game.cash());
+
+        emitln!("  ┌─────────────────────────┬─────────────────────────┐");
+        emitln!("  │        {:<17}│{:^25}│", t!("stash"), t!("trench_coat"));
+        emitln!("  ├─────────────────────────┼─────────────────────────┤");
+        for drug in self.game.catalog().drugs() {
+            let name: String = self.drug_name(drug).chars().take(9).collect();
+            emitln!(
+                "  │ {:<9}  {:<6}       │ {:<9}  {:<6}       │",
+                name,
+                self.game.stash(drug),
+                name,
+                self.game.trench_coat(drug)
+            );
         }
+        emitln!("  │                         │                         │");
+        let label = |key: &str| -> String { t!(key).chars().take(10).collect() };
+        emitln!(
+            "  │ {:<11}\x1B[36m{:<12}\x1B[0m │ {:<11}{:<6}       │",
+            label("bank"),
+            bank_formatted,
+            label("guns"),
+            self.game.guns()
+        );
+        emitln!(
+            "  │ {:<11}\x1B[35m{:<8}\x1B[0m     │ {:<11}\x1B[32m{:<12}\x1B[0m │",
+            label("debt"),
+            debt_formatted,
+            label("cash"),
+            cash_formatted
+        );
+        emitln!("  └─────────────────────────┴─────────────────────────┘\n");
+    }
 
-        if self.guns == 0 {
-            print!("WILL YOU RUN? ");
-            io::stdout().flush().unwrap();
-            let reply = self.getch().unwrap().to_lowercase().next().unwrap();
-            println!("{reply}");
-            if reply == 'r' || reply == 'y' {
-                let mut rng = rand::thread_rng();
-                let getaway = rng.gen_range(1..=2);
-                if getaway == 1 {
-                    self.fight_hud();
-                    print!("\x1B[36mYOU LOST THEM IN THE ALLEYS !!\x1B[0m ");
-                    io::stdout().flush().unwrap();
-                    self.wait_for_key_press();
-                    self.check_doctor();
-                } else {
-                    self.fight_hud();
-                    self.firing_on_you();
-                }
-            } else {
-                self.fight();
-            }
+    // the hud a line at a time, with what's in the coat & the stash only,
+    // & only when something on it changed
+    fn read_out_hud(&mut self) {
+        let game = &self.game;
+        let hud = [
+            t!(
+                "read_out_day",
+                date = Self::game_date_str(game.day()),
+                days = game.days_left(),
+                place = self.place_name(game.location())
+            ),
+            t!(
+                "read_out_money",
+                cash = Self::format_number(game.cash()),
+                bank = Self::format_number(game.bank()),
+                debt = Self::format_number(game.debt()),
+                guns = game.guns(),
+                hold = game.hold()
+            ),
+            t!("read_out_coat", drugs = self.holdings(|drug| game.trench_coat(drug))),
+            t!("read_out_stash", drugs = self.holdings(|drug| game.stash(drug))),
+            String::new(),
+        ]
+        .join("\n");
+        if hud != self.read_out {
+            emitln!("{}", hud);
+            self.read_out = hud;
+        }
+    }
+
+    fn holdings(&self, amount: impl Fn(Drug) -> i64) -> String {
+        let held: Vec<String> = self
+            .game
+            .catalog()
+            .drugs()
+            .filter(|drug| amount(*drug) > 0)
+            .map(|drug| format!("{} {}", self.drug_name(drug), amount(drug)))
+            .collect();
+        if held.is_empty() {
+            t!("empty").to_string()
         } else {
-            print!("WILL YOU RUN OR FIGHT? ");
-            io::stdout().flush().unwrap();
-            let reply = self.getch().unwrap().to_lowercase().next().unwrap();
-            println!("{reply}");
-            if reply == 'r' {
-                let mut rng = rand::thread_rng();
-                let getaway = rng.gen_range(1..=2);
-                if getaway == 1 {
-                    self.fight_hud();
-                    print!("\x1B[36mYOU LOST THEM IN THE ALLEYS !!\x1B[0m ");
-                    io::stdout().flush().unwrap();
-                    self.wait_for_key_press();
-                    self.check_doctor();
-                } else {
-                    self.fight_hud();
-                    self.firing_on_you();
-                }
-            } else if reply == 'f' {
-                self.fight_hud();
-                print!("YOU'RE FIRING ON THEM!! ");
-                io::stdout().flush().unwrap();
-                self.wait_for_key_press();
-                let mut rng = rand::thread_rng();
-                let kill_them = rng.gen_range(0..=self.guns * 2);
-                if kill_them == 0 {
-                    self.fight_hud();
-                    print!("YOU MISSED THEM !! ");
-                    io::stdout().flush().unwrap();
-                    self.wait_for_key_press();
-                    self.firing_on_you();
-                } else {
-                    self.fight_hud();
-                    self.cops -= 1;
-                    if self.cops <= 0 {
-                        self.fight_hud();
-                        print!("\x1B[32mYOU KILLED ALL OF THEM!!!!\x1B[0m ");
-                        io::stdout().flush().unwrap();
-                        self.wait_for_key_press();
-                        self.fight_reward();
-                    } else {
-                        print!("\x1B[33mYOU KILLED ONE!!\x1B[0m ");
-                        io::stdout().flush().unwrap();
-                        self.wait_for_key_press();
-                        self.firing_on_you();
-                    }
-                }
+            held.join(", ")
+        }
+    }
+
+    // a drug a row, with the lowest, highest & average you've seen it at,
+    // & what's left to buy & sell when drugs are limited
+    fn show_prices(&self) {
+        let game = &self.game;
+        emitln!("{}", t!("prices"));
+        emitln!();
+        let mut heading = vec!["drug", "price", "low", "high", "average"];
+        if game.rules().limited {
+            heading.extend(["supply", "demand"]);
+        }
+        let width = (self.width.saturating_sub(4) / heading.len()).clamp(8, 11);
+        let heading: String = heading.iter().map(|key| format!("{:<width$}", t!(key))).collect();
+        emitln!("    \x1B[36m{}\x1B[0m", heading.trim_end());
+        let cell = |n: Option<i64>| {
+            let text = n.map_or("-".to_string(), Self::format_number);
+            format!("{text:<width$}")
+        };
+        for drug in game.catalog().drugs() {
+            let price = if game.sold_here(drug) {
+                cell(Some(game.price(drug)))
+            } else if game.none_today(drug) {
+                format!("\x1B[31m{:<width$}\x1B[0m", t!("none"))
             } else {
-                self.fight();
+                cell(None)
+            };
+            let spread = game.history().spread(drug);
+            let name: String = self.drug_name(drug).chars().take(width - 1).collect();
+            let mut line = format!(
+                "    {name:<width$}{price}\x1B[32m{}\x1B[31m{}\x1B[33m{}",
+                cell(spread.map(|spread| spread.low)),
+                cell(spread.map(|spread| spread.high)),
+                cell(spread.map(|spread| spread.average))
+            );
+            if game.rules().limited {
+                let stock = game.stock(drug);
+                line.push_str("\x1B[0m");
+                line.push_str(&cell(stock.map(|stock| stock.supply)));
+                line.push_str(&cell(stock.map(|stock| stock.demand)));
             }
+            emitln!("{}\x1B[0m", line.trim_end());
         }
+        emitln!();
     }
 
-    fn firing_on_you(&mut self) {
-        self.fight_hud();
-        print!("THEY ARE FIRING ON YOU MAN !! ");
-        io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-        let mut rng = rand::thread_rng();
-        let damage_hit = rng.gen_range(0..=3) * self.cops - rng.gen_range(2..=18);
-        if damage_hit <= 0 {
-            self.fight_hud();
-            print!("THEY MISSED !! ");
-            io::stdout().flush().unwrap();
-            self.wait_for_key_press();
-            self.fight();
-        } else {
-            self.damage += damage_hit;
-            self.fight_hud();
-            print!("\x1B[31mYOU'VE BEEN HIT !!\x1B[0m ");
-            io::stdout().flush().unwrap();
-            self.wait_for_key_press();
-            self.fight();
+    // two columns of drugs & what they cost, - where nobody deals them
+    fn price_list(&self, heading: &str, price: impl Fn(Drug) -> Option<i64>) {
+        emitln!("{}", heading);
+        emitln!();
+        let drugs: Vec<Drug> = self.game.catalog().drugs().collect();
+        let rows = drugs.len().div_ceil(2);
+        for row in 0..rows {
+            let mut line = String::from("   ");
+            for drug in [drugs.get(row), drugs.get(row + rows)].into_iter().flatten() {
+                let price = price(*drug).map_or("-".to_string(), Self::format_number);
+                line.push_str(&format!(" {:<11}{:<11}   ", self.drug_name(*drug), price));
+            }
+            emitln!("{}", line.trim_end());
         }
+        emitln!();
     }
 
-    fn check_doctor(&mut self) {
-        if self.damage > 10 {
-            self.doctor();
-        } else {
-            self.main_menu();
+    // a block a day, as high as the price between the lowest & the highest
+    fn sparkline(prices: &[Option<i64>]) -> String {
+        const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let low = prices.iter().flatten().min().copied().unwrap_or(0);
+        let high = prices.iter().flatten().max().copied().unwrap_or(0);
+        prices
+            .iter()
+            .map(|price| match price {
+                None => ' ',
+                Some(_) if high == low => BLOCKS[3],
+                Some(price) => BLOCKS[((price - low) * 7 / (high - low)) as usize],
+            })
+            .collect()
+    }
+
+    // how long ago a price was seen, or when the informant's is for
+    fn age(today: i64, day: i64) -> String {
+        match today - day {
+            n if n < 0 => t!("tomorrow").to_string(),
+            0 => t!("today").to_string(),
+            1 => t!("day_ago").to_string(),
+            n => t!("days_ago", n = n),
         }
     }
 
-    fn doctor(&mut self) {
-        self.fight_hud();
-        let mut rng = rand::thread_rng();
-        let doc_price_multiplier = rng.gen_range(200..=1000);
-        let total_cost = self.damage * doc_price_multiplier / 10;
-
-        self.yn_prompt(
-            &format!(
-                "\x1B[36mWILL YOU PAY {} DOLLARS TO HAVE A DOCTOR SEW YOU UP?\x1B[0m ",
-                total_cost
+    // what's in your coat & the prices here & wherever you've been, in as
+    // many blocks of drugs as the window needs
+    fn show_seen(&self) {
+        let game = &self.game;
+        let cell = |price: i64| {
+            if price > 0 {
+                Self::format_number(price)
+            } else {
+                "-".to_string()
+            }
+        };
+        let coat = game.catalog().drugs().map(|drug| game.trench_coat(drug).to_string());
+        let here = game.prices().row(game.location()).into_iter().map(cell);
+        let mut rows = vec![
+            (t!("coat").to_string(), String::new(), coat.collect::<Vec<_>>()),
+            (
+                self.place_name(game.location()).to_string(),
+                t!("today").to_string(),
+                here.collect(),
             ),
-            |s| {
-                if s.cash >= total_cost {
-                    s.cash -= total_cost;
-                    s.damage = 0;
+        ];
+        for location in game.map().locations().filter(|l| *l != game.location()) {
+            if let Some(seen) = game.seen(location) {
+                let prices = seen.prices.iter().map(|price| cell(*price)).collect();
+                let age = Self::age(game.day(), seen.day);
+                rows.push((self.place_name(location).to_string(), age, prices));
+            }
+        }
+
+        let name = |drug| self.drug_name(drug).chars().take(7).collect();
+        let drugs: Vec<String> = game.catalog().drugs().map(name).collect();
+        let width = |text: &String| text.chars().count();
+        let heading = t!("last_seen").to_string();
+        let names = rows.iter().map(|row| &row.0).chain([&heading]);
+        let name_width = names.map(width).max().unwrap_or(0) + 2;
+        let age_width = rows.iter().map(|row| width(&row.1)).max().unwrap_or(0) + 2;
+        let cells = rows.iter().flat_map(|row| row.2.iter()).chain(&drugs);
+        let cell_width = cells.map(width).max().unwrap_or(0) + 1;
+        let room = self.width.saturating_sub(2 + name_width + age_width);
+        let per_block = (room / cell_width).max(1);
+
+        let columns: Vec<usize> = (0..drugs.len()).collect();
+        for (block, columns) in columns.chunks(per_block).enumerate() {
+            if block > 0 {
+                emitln!();
+            }
+            let mut line = format!("  {heading:<name_width$}{:<age_width$}", "");
+            for column in columns {
+                line.push_str(&format!("{:<cell_width$}", drugs[*column]));
+            }
+            emitln!("\x1B[36m{}\x1B[0m", line.trim_end());
+            for (name, age, prices) in &rows {
+                let mut line = format!("  {name:<name_width$}\x1B[33m{age:<age_width$}\x1B[0m");
+                for column in columns {
+                    line.push_str(&format!("{:<cell_width$}", prices[*column]));
                 }
-                s.main_menu();
-            },
-            GameState::main_menu,
-        );
+                emitln!("{}", line.trim_end());
+            }
+        }
     }
 
-    fn fight_reward(&mut self) {
-        self.fight_hud();
-        let mut rng = rand::thread_rng();
-        let fight_reward = rng.gen_range(200..=1000);
-        self.cash += fight_reward;
-
-        print!(
-            "\x1B[32mYOU FOUND {} DOLLARS ON OFFICER HARDASS' CARCASS !!\x1B[0m ",
-            fight_reward
-        );
-        io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-
-        self.check_doctor();
+    fn drug_name(&self, drug: Drug) -> &str {
+        locale().name(self.game.catalog().name(drug))
     }
 
-    fn roll_event(&mut self) {
-        let mut possible_events: Vec<fn(&mut GameState)> = Vec::new();
+    fn place_name(&self, location: Location) -> &str {
+        locale().name(self.game.map().name(location))
+    }
 
-        if *self.trench_coat.get(&Drug::Weed).unwrap_or(&0) > 1 {
-            possible_events.push(GameState::brownies);
+    fn yn_prompt(&mut self, prompt: &str) -> bool {
+        emit!("{prompt}");
+        io::stdout().flush().unwrap();
+        loop {
+            let reply = self.getch().to_ascii_lowercase();
+            if reply == key("yes") {
+                return true;
+            } else if reply == key("no") {
+                return false;
+            }
         }
-        if self.hold < 32 {
-            possible_events.push(GameState::policedogs);
+    }
+
+    fn loan(&mut self) -> Phase {
+        if !self.game.here().loan_shark {
+            return Phase::Stash;
         }
-        if self.hold > 32 {
-            possible_events.push(GameState::finddrugs);
+        self.hud();
+        if self.yn_prompt(&format!("{} ", t!("visit_loan_shark"))) {
+            let _ = self.game.apply(Command::LoanShark);
+            Phase::Repay
+        } else {
+            Phase::Stash
         }
-
-        possible_events.push(GameState::paraquat);
-        possible_events.push(GameState::mugged);
-        possible_events.push(GameState::cokebust);
-        possible_events.push(GameState::addicts);
-        possible_events.push(GameState::weedbottomout);
-        possible_events.push(GameState::coatsale);
-        possible_events.push(GameState::cheapheroin);
-        possible_events.push(GameState::cheapcocaine);
-        possible_events.push(GameState::cheapludes);
-        possible_events.push(GameState::cheapacid);
-        possible_events.push(GameState::gunsale);
-
-        let mut rng = rand::thread_rng();
-        let event = possible_events[rng.gen_range(0..possible_events.len())];
-
-        event(self);
     }
 
-    fn brownies(&mut self) {
-        let mut rng = rand::thread_rng();
-        let n = rng.gen_range(1..=5);
-        let trench_weed = self.trench_coat.get_mut(&Drug::Weed).unwrap();
-        let dropped = *trench_weed / n;
-        self.hold += dropped;
-        *trench_weed -= dropped;
+    fn repay(&mut self) -> Phase {
         self.hud();
-        print!("\x1B[31mYOUR MAMA MADE SOME BROWNIES AND USED YOUR WEED !!\x1B[0m ");
-        io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-        print!("\x1B[31mTHEY WERE GREAT !!\x1B[0m ");
+        emit!("{} ", t!("how_much_repay"));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        let amount = self.read_amount(self.game.cash().min(self.game.debt()), true);
+        if amount == 0 || self.game.apply(Command::Repay(amount)).is_ok() {
+            Phase::Borrow
+        } else {
+            Phase::Repay
+        }
     }
 
-    fn paraquat(&mut self) {
+    fn borrow(&mut self) -> Phase {
         self.hud();
-        println!("\x1B[35mTHERE IS SOME WEED THAT SMELLS LIKE PARAQUAT HERE !! IT LOOKS GOOD !!\x1B[0m ");
+        emit!("{} ", t!("how_much_borrow"));
         io::stdout().flush().unwrap();
-        self.yn_prompt(
-            "\x1B[35mWILL YOU SMOKE IT? \x1B[0m",
-            |s| {
-                s.hud();
-                print!("\x1B[38;2;121;112;169mYOU HALLUCINATE FOR THREE DAYS ON THE WILDEST TRIP YOU EVER IMAGINED !!\x1B[0m ");
-                io::stdout().flush().unwrap();
-                s.wait_for_key_press();
-                print!("\x1B[38;2;121;112;169mTHEN YOU DIE BECAUSE YOUR BRAIN HAS DISINTEGRATED !!\x1B[0m ");
-                io::stdout().flush().unwrap();
-                s.wait_for_key_press();
-                s.you_win();
-            },
-            GameState::main_menu,
-        );
+        // asking for too much gets the loan shark's answer, not a complaint
+        let amount = self.read_amount(self.game.max_loan() - self.game.debt(), false);
+        if amount == 0 {
+            return Phase::Stash;
+        }
+        match self.game.apply(Command::Borrow(amount)) {
+            Ok(_) => Phase::Stash,
+            Err(Error::LoanTooBig) => {
+                emitln!("{}", t!("crazy"));
+                thread::sleep(Duration::from_secs(1));
+                Phase::Borrow
+            }
+            Err(_) => Phase::Borrow,
+        }
     }
 
-    fn mugged(&mut self) {
-        self.cash = self.cash * 4 / 5;
+    fn stash(&mut self) -> Phase {
+        if !self.game.here().stash {
+            return Phase::Banking;
+        }
         self.hud();
-        print!("\x1B[31mYOU WERE MUGGED IN THE SUBWAY !!\x1B[0m ");
-        io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        if self.yn_prompt(&format!("{} ", t!("visit_stash"))) {
+            Phase::Stashing
+        } else {
+            Phase::Banking
+        }
     }
 
-    fn cokebust(&mut self) {
+    fn stashing(&mut self) -> Phase {
         self.hud();
-        let coke_price = self.prices.get_mut(&Drug::Cocaine).unwrap();
-        *coke_price *= 6;
-        print!("\x1B[36mCOPS MADE A BIG COKE BUST !! PRICES ARE OUTRAGEOUS !!\x1B[0m ");
+        emit!("{} ", t!("which_stash"));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        let reply = self.getch().to_lowercase().next().unwrap();
+        emitln!("{reply}");
+        match self.game.catalog().from_key(reply) {
+            Some(drug) => Phase::StashDeposit(drug),
+            None => Phase::Stash,
+        }
     }
 
-    fn addicts(&mut self) {
+    fn stash_deposit(&mut self, drug: Drug) -> Phase {
         self.hud();
-        let heroin_price = self.prices.get_mut(&Drug::Heroin).unwrap();
-        *heroin_price *= 6;
-        print!("\x1B[36mADDICTS ARE BUYING HEROIN AT OUTRAGEOUS PRICES !!\x1B[0m ");
+        emit!("{} ", t!("how_much_stash", drug = self.drug_name(drug)));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        let amount = self.read_amount(self.game.trench_coat(drug), true);
+
+        if amount != 0 && self.game.apply(Command::Stash { drug, amount }).is_err() {
+            Phase::Stash
+        } else {
+            Phase::StashTake(drug)
+        }
     }
 
-    fn weedbottomout(&mut self) {
+    fn stash_take(&mut self, drug: Drug) -> Phase {
         self.hud();
-        let weed_price = self.prices.get_mut(&Drug::Weed).unwrap();
-        *weed_price /= 5;
-        print!("\x1B[33mCOLOMBIAN FREIGHTER DUSTED THE COAST GUARD !!  WEED PRICES HAVE BOTTOMED OUT !!\x1B[0m ");
+        emit!("{} ", t!("how_much_take", drug = self.drug_name(drug)));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-    }
+        let amount = self.read_amount(self.game.stash(drug).min(self.game.hold()), true);
 
-    fn policedogs(&mut self) {
-        let mut rng = rand::thread_rng();
-        let n = rng.gen_range(2..=5);
+        if amount != 0 && self.game.apply(Command::Take { drug, amount }).is_err() {
+            Phase::Stash
+        } else {
+            Phase::Banking
+        }
+    }
 
-        for drug in [
-            Drug::Cocaine,
-            Drug::Heroin,
-            Drug::Acid,
-            Drug::Weed,
-            Drug::Speed,
-            Drug::Ludes,
-        ]
-        .iter()
-        {
-            let trench_amount = self.trench_coat.get_mut(drug).unwrap();
-            let amount = *trench_amount;
-            let dropped = amount / n;
-            *trench_amount -= dropped;
-            self.hold += dropped;
+    fn banking(&mut self) -> Phase {
+        if !self.game.here().bank {
+            return Phase::Informant;
         }
+        self.hud();
+        if self.yn_prompt(&format!("{} ", t!("visit_bank"))) {
+            Phase::Bank
+        } else {
+            Phase::Informant
+        }
+    }
 
+    fn visit_bank(&mut self) -> Phase {
         self.hud();
-        print!(
-            "\x1B[31mPOLICE DOGS CHASE YOU {} BLOCKS !!\x1B[0m ",
-            n
-        );
+        emit!("{} ", t!("how_much_deposit"));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-        print!(
-            "\x1B[31mYOU DROPPED SOME DRUGS !! THAT'S A DRAG MAN !!\x1B[0m "
-        );
+        let amount = self.read_amount(self.game.cash(), true);
+        let _ = self.game.apply(Command::Deposit(amount));
+        self.hud();
+        emit!("{} ", t!("how_much_withdraw"));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        let amount = self.read_amount(self.game.bank(), true);
+        let _ = self.game.apply(Command::Withdraw(amount));
+        Phase::Informant
     }
 
-    fn cheapcocaine(&mut self) {
+    // only asked when you can pay him & he hasn't tipped you off today
+    fn informant(&mut self) -> Phase {
+        let price = self.game.rules().informant;
+        let here = self.game.here().informant && !self.game.prices().has_forecast();
+        if !here || price > self.game.cash() {
+            return Phase::Market;
+        }
         self.hud();
-        let coke_price = self.prices.get_mut(&Drug::Cocaine).unwrap();
-        *coke_price /= 6;
-        print!("\x1B[32mPIGS ARE SELLING CHEAP COCAINE FROM LAST WEEKS RAID !!\x1B[0m ");
-        io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        let prompt = t!("visit_informant", price = Self::format_number(price));
+        if self.yn_prompt(&format!("{prompt} ")) {
+            Phase::Tip
+        } else {
+            Phase::Market
+        }
     }
 
-    fn cheapheroin(&mut self) {
+    fn tip(&mut self) -> Phase {
         self.hud();
-        let heroin_price = self.prices.get_mut(&Drug::Heroin).unwrap();
-        *heroin_price /= 6;
-        print!("\x1B[32mPIGS ARE SELLING CHEAP HEROIN FROM LAST WEEKS RAID !!\x1B[0m ");
+        self.show_places();
+        emit!("{} ", t!("which_tip"));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
-    }
-
-    fn finddrugs(&mut self) {
-        let mut rng = rand::thread_rng();
-        let n = rng.gen_range(1..=32);
-        let drug = match rng.gen_range(1..=6) {
-            1 => Drug::Cocaine,
-            2 => Drug::Heroin,
-            3 => Drug::Acid,
-            4 => Drug::Weed,
-            5 => Drug::Speed,
-            _ => Drug::Ludes,
+        let reply = self.getch();
+        emitln!("{reply}");
+        let Some(location) = self.game.map().from_key(reply) else {
+            return Phase::Market;
         };
+        if self.game.apply(Command::Tip(location)).is_err() {
+            return Phase::Tip;
+        }
 
-        let trench_amount = self.trench_coat.get_mut(&drug).unwrap();
-        *trench_amount += n;
-        self.hold -= n;
         self.hud();
-        print!(
-            "\x1B[36mYOU FIND {} UNITS OF {} ON A DEAD DUDE IN THE SUBWAY !!\x1B[0m ",
-            n,
-            drug.as_str()
-        );
+        let heading = t!("tip", place = self.place_name(location));
+        if let Some(seen) = self.game.seen(location) {
+            self.price_list(&heading, |drug| Some(seen.price(drug)).filter(|price| *price > 0));
+        }
+        emit!("\x1B[33m{}\x1B[0m ", t!("hit_any_key"));
         io::stdout().flush().unwrap();
         self.wait_for_key_press();
+        Phase::Market
     }
 
-    fn cheapludes(&mut self) {
+    fn buy_sell_jet(&mut self) -> Phase {
         self.hud();
-        let ludes_price = self.prices.get_mut(&Drug::Ludes).unwrap();
-        *ludes_price /= 6;
-        print!("\x1B[33mRIVAL DRUG DEALERS RAIDED A PHARMACY AND ARE SELLING CHEAP LUDES !!\x1B[0m ");
+        self.show_prices();
+        emit!("{} ", t!("buy_sell_jet"));
         io::stdout().flush().unwrap();
-        self.wait_for_key_press();
+        loop {
+            let reply = self.getch().to_ascii_lowercase();
+            let phase = if reply == key("buy") {
+                Phase::Buying
+            } else if reply == key("sell") {
+                Phase::Selling
+            } else if reply == key("jet") {
+                Phase::Jet
+            } else if reply == key("save") {
+                Phase::Save
+            } else if reply == key("history") {
+                Phase::History
+            } else if reply == key("informant") && self.game.here().informant {
+                Phase::Informant
+            } else {
+                continue;
+            };
+            emitln!();
+            return phase;
+        }
     }
 
-    fn cheapacid(&mut self) {
+    fn buying(&mut self) -> Phase {
+        emit!("{} ", t!("what_buy"));
+        io::stdout().flush().unwrap();
+        let reply = self.getch().to_lowercase().next().unwrap();
+        emitln!("{reply}");
+        match self.game.catalog().from_key(reply) {
+            Some(drug) => Phase::BuyDrug(drug),
+            None => Phase::Market,
+        }
+    }
+
+    fn buy_drug(&mut self, drug: Drug) -> Phase {
         self.hud();
-        let acid_price = self.prices.get_mut(&Drug::Acid).unwrap();
-        *acid_price /= 10;
-        print!("\x1B[33mTHE MARKET HAS BEEN FLOODED WITH CHEAP HOME MADE ACID !!\x1B[0m ");
+        self.show_prices();
+        if self.none_today(drug) {
+            return Phase::Market;
+        }
+
+        let mut most = self.game.afford(drug).min(self.game.hold());
+        emitln!("{}", t!("can_afford", n = self.game.afford(drug)));
+        if let Some(stock) = self.game.stock(drug) {
+            emitln!("{}", t!("they_have", n = stock.supply));
+            most = most.min(stock.supply);
+        }
+        emit!("{} ", t!("how_much_buy", drug = self.drug_name(drug)));
+        io::stdout().flush().unwrap();
+        let amount = self.read_amount(most, true);
+
+        let _ = self.game.apply(Command::Buy { drug, amount });
+        Phase::Market
+    }
+
+    // a drug that's dealt here but isn't around today
+    fn none_today(&mut self, drug: Drug) -> bool {
+        if !self.game.none_today(drug) {
+            return false;
+        }
+        emit!("\x1B[31m{}\x1B[0m ", t!("none_today", drug = self.drug_name(drug)));
         io::stdout().flush().unwrap();
         self.wait_for_key_press();
+        true
     }
 
-    fn gunsale(&mut self) {
+    fn selling(&mut self) -> Phase {
+        emit!("{} ", t!("what_sell"));
+        io::stdout().flush().unwrap();
+        let reply = self.getch().to_lowercase().next().unwrap();
+        emitln!("{reply}");
+        match self.game.catalog().from_key(reply) {
+            Some(drug) => Phase::SellDrug(drug),
+            None => Phase::Market,
+        }
+    }
+
+    fn sell_drug(&mut self, drug: Drug) -> Phase {
         self.hud();
-        let gunstock = ["RUGER", ".38 SPECIAL", "SATURDAY NIGHT SPECIAL", "BARETTA"];
-        let mut rng = rand::thread_rng();
-        let shufguns = rng.gen_range(0..gunstock.len());
-        self.gunprice = rng.gen_range(250..=500);
-        print!(
-            "\x1B[34mWILL YOU BUY A {} FOR {}?\x1B[0m ",
-            gunstock[shufguns], self.gunprice
-        );
+        self.show_prices();
+        if self.none_today(drug) {
+            return Phase::Market;
+        }
+
+        let mut most = self.game.trench_coat(drug);
+        emitln!("{}", t!("can_sell", n = most));
+        if let Some(stock) = self.game.stock(drug) {
+            emitln!("{}", t!("they_take", n = stock.demand));
+            most = most.min(stock.demand);
+        }
+        emit!("{} ", t!("how_much_sell", drug = self.drug_name(drug)));
         io::stdout().flush().unwrap();
-        self.yn_prompt(
-            "",
-            |s| {
-                if s.cash >= s.gunprice {
-                    s.cash -= s.gunprice;
-                    s.guns += 1;
-                }
-            },
-            |_| {},
+        let amount = self.read_amount(most, true);
+
+        let _ = self.game.apply(Command::Sell { drug, amount });
+        Phase::Market
+    }
+
+    fn jet(&mut self) -> Phase {
+        self.paint(Self::draw_jet);
+        emit!("{} ", t!("where_to"));
+        io::stdout().flush().unwrap();
+        let reply = self.getch();
+        emitln!("{reply}");
+        match self.game.map().from_key(reply) {
+            Some(location) => {
+                self.arrival = if self.game.map().spec(location).has_services() {
+                    Phase::Loan
+                } else {
+                    Phase::Market
+                };
+                self.banner = None;
+                self.hud();
+                let events = self.game.apply(Command::Jet(location)).unwrap_or_default();
+                self.play(events)
+            }
+            None => Phase::Market,
+        }
+    }
+
+    // the places instead of the hud's box, with the prices last seen in them
+    fn draw_jet(&mut self) {
+        self.draw_day();
+        self.show_places();
+        self.show_seen();
+        emitln!();
+    }
+
+    fn show_places(&self) {
+        emitln!();
+        let locations: Vec<Location> = self.game.map().locations().collect();
+        let widths: Vec<usize> = (0..3)
+            .map(|col| {
+                locations
+                    .iter()
+                    .skip(col)
+                    .step_by(3)
+                    .map(|location| self.place_name(*location).chars().count() + 3)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for row in locations.chunks(3) {
+            let mut line = String::from("  ");
+            for (col, location) in row.iter().enumerate() {
+                line.push_str(&format!(
+                    " {}) {:<width$}",
+                    location.index() + 1,
+                    self.place_name(*location),
+                    width = widths[col]
+                ));
+            }
+            emitln!("{}", line.trim_end());
+        }
+        emitln!();
+    }
+
+    fn history(&mut self) -> Phase {
+        self.paint(Self::draw_history);
+        emit!("\x1B[33m{}\x1B[0m ", t!("hit_any_key"));
+        io::stdout().flush().unwrap();
+        self.wait_for_key_press();
+        Phase::Market
+    }
+
+    // every drug's price day by day, as many of the last days as fit
+    fn draw_history(&mut self) {
+        self.draw_day();
+        let game = &self.game;
+        let history = game.history();
+        emitln!("\x1B[1;32m{}\x1B[0m", t!("history_title"));
+        emitln!();
+        if READER.load(AtomicOrdering::Relaxed) {
+            for drug in game.catalog().drugs() {
+                let line = match history.spread(drug) {
+                    Some(spread) => t!(
+                        "read_out_history",
+                        drug = self.drug_name(drug),
+                        low = Self::format_number(spread.low),
+                        high = Self::format_number(spread.high),
+                        average = Self::format_number(spread.average)
+                    ),
+                    None => format!("{}: {}", self.drug_name(drug), t!("never_seen")),
+                };
+                emitln!("{}", line);
+            }
+            emitln!();
+            return;
+        }
+
+        let room = self.width.saturating_sub(4 + 11 + 9 * 3).max(1);
+        let first = (game.day() + 1 - room as i64).max(0);
+        let days = t!("history_days", first = first + 1, last = game.day() + 1);
+        emitln!(
+            "    \x1B[36m{:<11}{:<9}{:<9}{:<9}{}\x1B[0m",
+            t!("drug"),
+            t!("low"),
+            t!("high"),
+            t!("average"),
+            days
         );
+        let places: String = history
+            .places(game.day())
+            .into_iter()
+            .skip(first as usize)
+            .map(|place| {
+                place.map_or(' ', |place| {
+                    char::from_digit(place.index() as u32 + 1, 36).unwrap_or('+')
+                })
+            })
+            .collect();
+        emitln!("    {:<38}\x1B[34m{}\x1B[0m", t!("where"), places);
+        let cell = |price: Option<i64>| price.map_or("-".to_string(), Self::format_number);
+        for drug in game.catalog().drugs() {
+            let spread = history.spread(drug);
+            let prices = history.daily(drug, game.day());
+            emitln!(
+                "    {:<11}\x1B[32m{:<9}\x1B[31m{:<9}\x1B[33m{:<9}\x1B[0m{}",
+                self.drug_name(drug).chars().take(10).collect::<String>(),
+                cell(spread.map(|spread| spread.low)),
+                cell(spread.map(|spread| spread.high)),
+                cell(spread.map(|spread| spread.average)),
+                Self::sparkline(&prices[first as usize..]).trim_end()
+            );
+        }
+        emitln!();
     }
 
-    fn coatsale(&mut self) {
+    fn save_game(&mut self) -> Phase {
         self.hud();
-        let mut rng = rand::thread_rng();
-        self.coatspace = rng.gen_range(32..=64);
-        self.coatprice = rng.gen_range(150..=400);
-        print!(
-            "\x1B[38;2;255;202;128mWILL YOU BUY A NEW TRENCH COAT WITH MORE POCKETS FOR {}?\x1B[0m ",
-            self.coatprice
-        );
-        io::stdout().flush().unwrap();
-        self.yn_prompt(
-            "",
-            |s| {
-                if s.cash >= s.coatprice {
-                    s.cash -= s.coatprice;
-                    s.hold += s.coatspace;
-                }
+        match &self.save_path {
+            Some(path) => match save::save(&self.game, path) {
+                Ok(()) => emit!("\x1B[32m{}\x1B[0m ", t!("saved", path = path.display())),
+                Err(e) => emit!("\x1B[31m{}\x1B[0m ", t!("cant_save", error = e)),
             },
-            |_| {},
-        );
+            None => emit!("\x1B[31m{}\x1B[0m ", t!("nowhere_to_save")),
+        }
+        io::stdout().flush().unwrap();
+        self.wait_for_key_press();
+        Phase::Market
     }
 
-    fn get_drug_from_char(c: char) -> Option<Drug> {
-        match c.to_lowercase().next().unwrap() {
-            'c' => Some(Drug::Cocaine),
-            'h' => Some(Drug::Heroin),
-            'a' => Some(Drug::Acid),
-            'w' => Some(Drug::Weed),
-            's' => Some(Drug::Speed),
-            'l' => Some(Drug::Ludes),
-            _ => None,
+    // shows what the engine did, then works out what it is waiting on
+    fn play(&mut self, events: Vec<Event>) -> Phase {
+        for event in events {
+            self.show_event(event);
+        }
+        if self.game.is_over() {
+            Phase::GameOver
+        } else if let Some(offer) = self.game.offer() {
+            Phase::Offer(offer)
+        } else if self.game.in_fight() {
+            Phase::Fight
+        } else {
+            self.arrival
         }
     }
 
-    fn read_number_input(&self) -> i64 {
-        let mut reply = String::new();
-        io::stdin().read_line(&mut reply).unwrap();
-        reply.trim().parse().unwrap_or(0)
+    fn answer_offer(&mut self, offer: Offer) -> Phase {
+        let (color, prompt) = Self::offer_prompt(offer);
+        let command = if self.yn_prompt(&format!("{}{}\x1B[0m ", color_code(color), prompt)) {
+            Command::Accept
+        } else {
+            Command::Decline
+        };
+        let events = self.game.apply(command).unwrap_or_default();
+        self.play(events)
     }
 
-    fn getch(&mut self) -> io::Result<char> {
-        #[cfg(unix)]
-        {
-            use std::io::Read;
+    fn offer_prompt(offer: Offer) -> (&'static str, String) {
+        match offer {
+            Offer::Paraquat => ("magenta", t!("offer_paraquat").to_string()),
+            Offer::Gun { gun, price } => {
+                ("blue", t!("offer_gun", gun = locale().name(gun), price = price))
+            }
+            Offer::Coat { price, .. } => ("#ffca80", t!("offer_coat", price = price)),
+            Offer::Doctor { cost } => ("cyan", t!("offer_doctor", cost = cost)),
+        }
+    }
 
-            let stdin = io::stdin();
-            let mut handle = stdin.lock();
+    fn is_fight_event(event: Event) -> bool {
+        matches!(
+            event,
+            Event::LostThem
+                | Event::YouFire
+                | Event::YouMissed
+                | Event::KilledOne
+                | Event::KilledAll { .. }
+                | Event::CopsFire
+                | Event::CopsMissed
+                | Event::Hit { .. }
+                | Event::Wasted
+        )
+    }
 
-            let mut termios = tcgetattr(STDIN_FILENO).unwrap();
-            let old_termios = termios.clone();
+    fn show_event(&mut self, event: Event) {
+        match event {
+            Event::GameOver(_) => return,
+            // the message & its key press are the same either way, so a
+            // journal replays whatever the profile knows
+            Event::Unlocked(achievement) if Self::is_new(self.player.as_deref(), achievement) => {
+                self.banner = Some(achievement)
+            }
+            _ => {}
+        }
+        if Self::is_fight_event(event) {
+            self.fight_hud();
+        } else {
+            self.hud();
+        }
 
-            cfmakeraw(&mut termios);
-            tcsetattr(STDIN_FILENO, SetArg::TCSANOW, &termios).unwrap();
+        // an offer asks its question straight after
+        let offer = match event {
+            Event::Random { id, .. } => self.game.events().spec(id).offer.is_some(),
+            _ => false,
+        };
+        let messages: Vec<String> = Self::event_lines(&self.game, event)
+            .into_iter()
+            .map(|(color, message)| format!("{}{}\x1B[0m ", color_code(color), message))
+            .collect();
+        for message in messages {
+            if offer {
+                emitln!("{message}");
+            } else {
+                emit!("{message}");
+                io::stdout().flush().unwrap();
+                self.wait_for_key_press();
+            }
+        }
+    }
 
-            let mut buffer = [0; 1];
-            let res = handle.read_exact(&mut buffer);
+    // what an event says & its color, for either front end
+    fn event_lines(game: &Game, event: Event) -> Vec<(&str, String)> {
+        match event {
+            Event::Random { id, drug, n } => {
+                let spec = game.events().spec(id);
+                let drug = drug.map_or("", |drug| locale().name(game.catalog().name(drug)));
+                locale()
+                    .event(&spec.name)
+                    .unwrap_or(&spec.messages)
+                    .iter()
+                    .map(|message| {
+                        let message =
+                            message.replace("{n}", &n.to_string()).replace("{drug}", drug);
+                        (spec.color.as_str(), message)
+                    })
+                    .collect()
+            }
+            Event::Hallucinated => vec![
+                ("#7970a9", t!("hallucinate").to_string()),
+                ("#7970a9", t!("brain_disintegrated").to_string()),
+            ],
+            Event::CopsChasing { cops } => vec![("red", t!("cops_chasing", cops = cops))],
+            Event::LostThem => vec![("cyan", t!("lost_them").to_string())],
+            Event::YouFire => vec![("", t!("you_fire").to_string())],
+            Event::YouMissed => vec![("", t!("you_missed").to_string())],
+            Event::KilledOne => vec![("yellow", t!("killed_one").to_string())],
+            Event::KilledAll { reward } => vec![
+                ("green", t!("killed_all").to_string()),
+                ("green", t!("found_reward", reward = reward)),
+            ],
+            Event::CopsFire => vec![("", t!("cops_fire").to_string())],
+            Event::CopsMissed => vec![("", t!("cops_missed").to_string())],
+            Event::Hit { .. } => vec![("red", t!("hit").to_string())],
+            Event::Wasted => vec![("red", t!("wasted").to_string())],
+            Event::Unlocked(achievement) => vec![(
+                "yellow",
+                t!(
+                    "unlocked",
+                    description = locale().name(achievement.description()).to_uppercase()
+                ),
+            )],
+            Event::GameOver(_) => Vec::new(),
+        }
+    }
 
-            tcsetattr(STDIN_FILENO, SetArg::TCSANOW, &old_termios).unwrap();
+    fn you_win(&mut self) {
+        clear_screen();
+        let score = self.game.score();
+        let total_money = score.total_money;
+        let total_money_display = Self::format_number(total_money);
 
-            match res {
-                Ok(()) => {
-                    if buffer[0] == 3 {
-                        self.you_win();
-                        process::exit(0);
-                    } else {
-                        Ok(buffer[0] as char)
+        let forfeit = self.game.end() == Some(End::Forfeit);
+        let days = format!("\x1B[33m{}\x1B[0m", self.game.day());
+        let (title, after) = if forfeit {
+            (t!("forfeited"), t!("gave_up", days = days))
+        } else {
+            (t!("game_over"), t!("survived", days = days))
+        };
+        emitln!("\x1B[38;2;255;202;128m{}\x1B[0m\n{}", title, after);
+        let money = format!("\x1B[32m{}\x1B[0m", total_money_display);
+        emitln!("{}", t!("total_money", money = money));
+        let points = format!("\x1B[35m{}\x1B[0m", score.score);
+        emitln!("{}", t!("your_score", score = points));
+
+        let dealer_rank = format!(
+            "\x1B[36m{} ... {}\x1B[0m",
+            locale().name(score.rank.as_str()),
+            locale().name(score.rank.motto())
+        );
+        emitln!("{}", t!("dealer_rank", rank = dealer_rank));
+        emitln!("{}", t!("seed", seed = format!("\x1B[33m{}\x1B[0m", self.game.seed())));
+        let difficulty = self.game.rules().difficulty;
+        if difficulty != Difficulty::Classic {
+            let name = format!("\x1B[33m{}\x1B[0m", Self::difficulty_name(difficulty));
+            emitln!("{}", t!("difficulty", difficulty = name));
+        }
+        let achievements: Vec<&str> =
+            self.game.achievements().iter().map(|a| locale().name(a.as_str())).collect();
+        if !achievements.is_empty() {
+            let names = format!("\x1B[33m{}\x1B[0m", achievements.join(", "));
+            emitln!("{}", t!("achievements", names = names));
+        }
+
+        // a forfeit still counts for the player's profile, under the name they have
+        let name = match &self.player {
+            Some(player) if forfeit => player.clone(),
+            _ if self.keep_score && !forfeit => self.player_name(),
+            _ => String::new(),
+        };
+        let (high_scores, errors) = Self::record_score(&self.game, name.clone(), self.keep_score);
+        for error in errors {
+            emitln!("\x1B[31m{}\x1B[0m", error);
+        }
+        if forfeit {
+            emitln!("\n{}", t!("forfeit_note"));
+        }
+        if self.keep_score && !name.is_empty() {
+            self.player = Some(name);
+        }
+
+        let top_ten = if difficulty == Difficulty::Classic {
+            t!("top_ten").to_string()
+        } else {
+            t!("top_ten_of", difficulty = Self::difficulty_name(difficulty))
+        };
+        emitln!("\x1B[38;2;255;202;128m\n{}\n\x1B[0m", top_ten);
+        let high_scores = high_scores.iter().filter(|hs| hs.difficulty == difficulty);
+        for (i, hs) in high_scores.enumerate() {
+            emitln!(
+                "{:02}  {:<12}  \x1B[32m{}\x1B[0m  \x1B[36m{}\x1B[0m  \x1B[35m{}\x1B[0m",
+                i + 1,
+                Self::score_name(hs),
+                Self::format_number(hs.total_money),
+                locale().name(&hs.dealer_rank),
+                hs.date
+            );
+        }
+    }
+
+    // adds the score & the player's game to their files, replays only show
+    // where the score would have landed, hands back the table & any trouble,
+    // a forfeit goes in the profile but never the table
+    fn record_score(game: &Game, name: String, keep_score: bool) -> (Vec<HighScore>, Vec<String>) {
+        let mut errors = Vec::new();
+        let forfeit = game.end() == Some(End::Forfeit);
+        let score = game.score();
+        let now = Local::now();
+        let new_score = HighScore {
+            name,
+            total_money: score.total_money,
+            dealer_rank: score.rank.as_str().to_string(),
+            // kept in one format whatever the language, it's saved for good
+            date: format!("{:02} / {:02} / {:02}", now.month(), now.day(), now.year() % 100),
+            difficulty: game.rules().difficulty,
+            seed: Some(game.seed()),
+            days: game.day(),
+            end: game.end(),
+            peak: game.peak_net_worth(),
+            time: now.timestamp(),
+        };
+
+        if keep_score && !new_score.name.is_empty() {
+            if let Some(path) = profile::default_path() {
+                if let Err(e) = profile::record(&path, &new_score.name, game) {
+                    errors.push(t!("cant_update_profile", error = e));
+                }
+            }
+        }
+
+        let path = highscores::default_path();
+        let recorded = match &path {
+            Some(path) if keep_score && !forfeit => {
+                match highscores::record(path, new_score.clone()) {
+                    Ok(scores) => Some(scores),
+                    Err(e) => {
+                        errors.push(t!("cant_save_score", error = e));
+                        None
                     }
                 }
-                Err(e) => Err(e),
             }
+            _ => None,
+        };
+        let high_scores = recorded.unwrap_or_else(|| {
+            let mut scores = match &path {
+                Some(path) if path.exists() => highscores::load(path).unwrap_or_default(),
+                _ => highscores::load_legacy(Path::new(highscores::LEGACY_PATH))
+                    .unwrap_or_default(),
+            };
+            if !forfeit {
+                scores.push(new_score);
+            }
+            highscores::top_ten(scores)
+        });
+        (high_scores, errors)
+    }
+
+    // an empty name keeps the current player
+    fn player_name(&mut self) -> String {
+        match &self.player {
+            Some(player) => emit!("\n{} ", t!("enter_name_or", player = player)),
+            None => emit!("\n{} ", t!("enter_name")),
         }
+        io::stdout().flush().unwrap();
+        let name = self.input.line().unwrap_or_default();
+        self.record(Input::Text(name.clone()));
+        let name: String = name.trim().to_uppercase().chars().take(12).collect();
+        if name.is_empty() {
+            self.player.clone().unwrap_or_default()
+        } else {
+            name
+        }
+    }
 
-        #[cfg(windows)]
-        #[allow(unused_imports)]
-        {
-            use winapi::um::wincon::{PeekConsoleInputW, ENABLE_LINE_INPUT, ENABLE_ECHO_INPUT, INPUT_RECORD, KEY_EVENT, KEY_EVENT_RECORD};
-            use winapi::um::consoleapi::{ReadConsoleInputW, SetConsoleCtrlHandler};
-            use winapi::um::winbase::STD_INPUT_HANDLE;
-            use winapi::um::handleapi::INVALID_HANDLE_VALUE;
-            use winapi::um::processenv::GetStdHandle;
-            use winapi::shared::minwindef::DWORD;
-            use std::sync::atomic::{AtomicBool, Ordering};
-            use std::{io, process};
-            use std::ptr::null_mut;
+    // only a first unlock for the player gets a banner
+    fn is_new(player: Option<&str>, achievement: Achievement) -> bool {
+        let known = player.and_then(|name| {
+            let profiles = profile::load(&profile::default_path()?).ok()?;
+            Some(profiles.find(name)?.achievements.contains(&achievement))
+        });
+        !known.unwrap_or(false)
+    }
 
-            static CTRL_C_HANDLED: AtomicBool = AtomicBool::new(false);
+    fn profiles(&mut self, index: usize) -> Phase {
+        self.term_info();
+        clear_screen();
+        let profiles = profile::default_path()
+            .and_then(|path| profile::load(&path).ok())
+            .unwrap_or_default();
+        let count = profiles.profiles.len();
+        emitln!("\x1B[38;2;255;202;128m\n{}\x1B[0m\n", t!("profiles"));
+        if count == 0 {
+            emitln!("{}", t!("no_profiles"));
+            emitln!();
+            emit!("{} ", t!("back_key", back = hotkey("back")));
+            io::stdout().flush().unwrap();
+            while self.getch().to_ascii_lowercase() != key("back") {}
+            return Phase::Title;
+        }
 
-            unsafe extern "system" fn ctrl_handler(_: u32) -> i32 {
-                CTRL_C_HANDLED.store(true, Ordering::SeqCst);
-                1 
-            }
+        let index = index % count;
+        let player = &profiles.profiles[index];
+        let playing = self.player.as_deref() == Some(player.name.as_str());
+        emitln!(
+            "\x1B[36m{}\x1B[0m{}    {}\n",
+            player.name,
+            if playing { format!(" ({})", t!("playing")) } else { String::new() },
+            t!("of", n = index + 1, count = count)
+        );
+        let spot = player.favorite_location().map_or("-", |spot| locale().name(spot));
+        let rows = [
+            ("games_played", Self::format_number(player.games)),
+            ("average_score", Self::format_number(player.average_score())),
+            ("best_score", Self::format_number(player.best_score)),
+            ("best_money", Self::format_number(player.best_money)),
+            ("cops_killed", Self::format_number(player.cops_killed)),
+            ("times_mugged", Self::format_number(player.times_mugged)),
+            ("doctor_bills", Self::format_number(player.doctor_bills)),
+            ("favorite_spot", spot.to_string()),
+        ];
+        for (label, value) in rows {
+            emitln!("{:<16}\x1B[33m{}\x1B[0m", t!(label), value);
+        }
 
-            unsafe {
-                SetConsoleCtrlHandler(Some(ctrl_handler), 1);
+        emitln!(
+            "\n\x1B[36m{:<16}{}\x1B[0m",
+            t!("achievements_heading"),
+            t!("of", n = player.achievements.len(), count = Achievement::ALL.len())
+        );
+        for achievement in Achievement::ALL {
+            if player.achievements.contains(&achievement) {
+                emitln!(
+                    "{:<16}{}",
+                    locale().name(achievement.as_str()),
+                    locale().name(achievement.description()).to_uppercase()
+                );
             }
+        }
 
-            unsafe {
-                let handle = GetStdHandle(STD_INPUT_HANDLE);
-                if handle == INVALID_HANDLE_VALUE {
-                    return Err(io::Error::last_os_error());
-                }
+        emitln!(
+            "\n\x1B[36m{:<16}{:<14}{}\x1B[0m",
+            t!("drug"),
+            t!("bought"),
+            t!("sold")
+        );
+        let mut drugs: Vec<&String> = player.bought.keys().chain(player.sold.keys()).collect();
+        drugs.sort();
+        drugs.dedup();
+        for drug in drugs {
+            emitln!(
+                "{:<16}{:<14}{}",
+                locale().name(drug),
+                Self::format_number(player.bought.get(drug).copied().unwrap_or(0)),
+                Self::format_number(player.sold.get(drug).copied().unwrap_or(0))
+            );
+        }
 
-                let mut mode: DWORD = 0;
-                if GetConsoleMode(handle, &mut mode) == 0 {
-                    return Err(io::Error::last_os_error());
+        emitln!();
+        let menu = t!(
+            "profile_keys",
+            next = hotkey("next"),
+            previous = hotkey("previous"),
+            play_as = hotkey("play_as"),
+            player = player.name,
+            back = hotkey("back")
+        );
+        emit!("{} ", menu);
+        io::stdout().flush().unwrap();
+        loop {
+            let reply = self.getch().to_ascii_lowercase();
+            if reply == key("next") {
+                return Phase::Profiles(index + 1);
+            } else if reply == key("previous") {
+                return Phase::Profiles(index + count - 1);
+            } else if reply == key("play_as") {
+                if let Some(path) = profile::default_path().filter(|_| self.keep_score) {
+                    let _ = profile::select(&path, &player.name);
                 }
+                self.player = Some(player.name.clone());
+                return Phase::Profiles(index);
+            } else if reply == key("back") || reply == '\x1B' {
+                return Phase::Title;
+            }
+        }
+    }
 
-                let new_mode = mode & !(ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT);
-                if SetConsoleMode(handle, new_mode) == 0 {
-                    return Err(io::Error::last_os_error());
-                }
+    fn score_name(score: &HighScore) -> &str {
+        if score.name.is_empty() {
+            t!("anonymous")
+        } else {
+            &score.name
+        }
+    }
 
-                let mut input_record: INPUT_RECORD = std::mem::zeroed();
-                let mut events_read = 0;
+    // header & rows, shared by the title screen & --scores
+    fn leaderboard(scores: &[HighScore], only: Option<Difficulty>, sort: SortBy) -> Vec<String> {
+        let last = if only.is_some() { t!("date") } else { t!("difficulty_heading") };
+        let mut lines = vec![format!(
+            "##  {:<12}  {:<13}{:<13}{:<6}{:<16}{}",
+            t!("name"),
+            t!("money"),
+            t!("peak"),
+            t!("days"),
+            t!("end"),
+            last
+        )];
+        for (i, hs) in highscores::sorted(scores, only, sort).iter().enumerate() {
+            let end = match hs.end {
+                Some(end) => locale().name(end.as_str()),
+                None => "-",
+            };
+            let last = if only.is_some() {
+                hs.date.clone()
+            } else {
+                Self::difficulty_name(hs.difficulty)
+            };
+            lines.push(format!(
+                "{:02}  {:<12}  {:<13}{:<13}{:<6}{:<16}{}",
+                i + 1,
+                Self::score_name(hs),
+                Self::format_number(hs.total_money),
+                Self::format_number(hs.peak),
+                hs.days,
+                end,
+                last
+            ));
+        }
+        lines
+    }
 
-                loop {
-                    if CTRL_C_HANDLED.load(Ordering::SeqCst) {
-                        self.you_win();
-                        process::exit(0);
+    fn scores(&mut self, only: Option<Difficulty>, sort: SortBy) -> Phase {
+        self.term_info();
+        clear_screen();
+        let scores = highscores::default_path()
+            .and_then(|path| highscores::load(&path).ok())
+            .unwrap_or_default();
+        let which = match only {
+            Some(difficulty) => Self::difficulty_name(difficulty),
+            None => t!("all").to_string(),
+        };
+        emitln!(
+            "\x1B[38;2;255;202;128m\n{}\x1B[0m    {}\n",
+            t!("high_scores"),
+            t!(
+                "scores_by",
+                difficulty = format!("\x1B[33m{}\x1B[0m", which),
+                sort = format!("\x1B[33m{}\x1B[0m", locale().name(sort.as_str()))
+            )
+        );
+        let lines = Self::leaderboard(&scores, only, sort);
+        emitln!("\x1B[36m{}\x1B[0m", lines[0]);
+        for line in &lines[1..] {
+            emitln!("{line}");
+        }
+        if lines.len() == 1 {
+            emitln!("{}", t!("no_scores"));
+        }
+        emitln!();
+        let menu = t!(
+            "score_keys",
+            difficulty = hotkey("difficulty"),
+            sort = hotkey("sort"),
+            back = hotkey("back")
+        );
+        emit!("{} ", menu);
+        io::stdout().flush().unwrap();
+        loop {
+            let reply = self.getch().to_ascii_lowercase();
+            if reply == key("difficulty") {
+                let next = match only {
+                    None => Some(Difficulty::ALL[0]),
+                    Some(difficulty) => {
+                        let i = Difficulty::ALL.iter().position(|d| *d == difficulty).unwrap();
+                        Difficulty::ALL.get(i + 1).copied()
                     }
+                };
+                return Phase::Scores(next, sort);
+            } else if reply == key("sort") {
+                return Phase::Scores(only, sort.next());
+            } else if reply == key("back") || reply == '\x1B' {
+                return Phase::Title;
+            }
+        }
+    }
 
-                    let peek_result = PeekConsoleInputW(handle, &mut input_record, 1, &mut events_read);
-                    if peek_result == 0 || events_read == 0 {
-                        continue; 
-                    }
+    fn fight_hud(&mut self) {
+        self.paint(Self::draw_fight_hud);
+    }
 
-                    if ReadConsoleInputW(handle, &mut input_record, 1, &mut events_read) == 0 {
-                        return Err(io::Error::last_os_error());
-                    }
+    fn draw_fight_hud(&mut self) {
+        self.term_info();
+        clear_screen();
+        emitln!();
+        if READER.load(AtomicOrdering::Relaxed) {
+            emitln!(
+                "{}\n",
+                t!(
+                    "read_out_fight",
+                    damage = self.game.damage(),
+                    cops = self.game.cops(),
+                    guns = self.game.guns()
+                )
+            );
+            return;
+        }
+        let bar = "█".repeat(self.width);
+        emitln!("\x1B[35m{}\x1B[0m", bar);
+        emitln!();
+        emitln!(
+            "{}{:<10}\x1B[35m{}\x1B[0m       {:<8}\x1B[36m{}\x1B[0m       {:<8}\x1B[34m{}\x1B[0m",
+            " ".repeat(self.width / 8),
+            t!("damage"),
+            self.game.damage(),
+            t!("cops"),
+            self.game.cops(),
+            t!("guns"),
+            self.game.guns()
+        );
+        emitln!();
+        emitln!("\x1B[35m{}\x1B[0m", bar);
+        emitln!("\n\n");
+    }
 
-                    if input_record.EventType == KEY_EVENT && input_record.Event.KeyEvent().bKeyDown != 0 {
-                        let key_event: KEY_EVENT_RECORD = *input_record.Event.KeyEvent();
-                        #[allow(clippy::cast_possible_truncation)]
-                        let char_code = *key_event.uChar.UnicodeChar() as u8 as char;
+    fn fight(&mut self) -> Phase {
+        self.fight_hud();
 
-                        if char_code == '\u{3}' {
-                            self.you_win();
-                            process::exit(0);
-                        }
+        let command = if self.game.guns() == 0 {
+            emit!("{} ", t!("will_you_run"));
+            io::stdout().flush().unwrap();
+            let reply = self.getch().to_lowercase().next().unwrap();
+            emitln!("{reply}");
+            if reply == key("run") || reply == key("yes") {
+                Some(Command::Run)
+            } else {
+                None
+            }
+        } else {
+            emit!("{} ", t!("run_or_fight"));
+            io::stdout().flush().unwrap();
+            let reply = self.getch().to_lowercase().next().unwrap();
+            emitln!("{reply}");
+            if reply == key("run") {
+                Some(Command::Run)
+            } else if reply == key("fight") {
+                Some(Command::Fight)
+            } else {
+                None
+            }
+        };
 
-                        return Ok(char_code);
+        match command.map(|c| self.game.apply(c)) {
+            Some(Ok(events)) => self.play(events),
+            _ => Phase::Fight,
+        }
+    }
+
+    fn run(&mut self, mut phase: Phase) {
+        loop {
+            self.playing = !matches!(
+                phase,
+                Phase::Title
+                    | Phase::Scores(..)
+                    | Phase::Profiles(_)
+                    | Phase::Instructions
+                    | Phase::GameOver
+            );
+            phase = match phase {
+                Phase::Title => self.start_game(),
+                Phase::Scores(only, sort) => self.scores(only, sort),
+                Phase::Profiles(index) => self.profiles(index),
+                Phase::Instructions => self.instructions(),
+                Phase::Begin => self.begin(),
+                Phase::Loan => self.loan(),
+                Phase::Repay => self.repay(),
+                Phase::Borrow => self.borrow(),
+                Phase::Stash => self.stash(),
+                Phase::Stashing => self.stashing(),
+                Phase::StashDeposit(drug) => self.stash_deposit(drug),
+                Phase::StashTake(drug) => self.stash_take(drug),
+                Phase::Banking => self.banking(),
+                Phase::Bank => self.visit_bank(),
+                Phase::Informant => self.informant(),
+                Phase::Tip => self.tip(),
+                Phase::Market => self.buy_sell_jet(),
+                Phase::Buying => self.buying(),
+                Phase::BuyDrug(drug) => self.buy_drug(drug),
+                Phase::Selling => self.selling(),
+                Phase::SellDrug(drug) => self.sell_drug(drug),
+                Phase::Jet => self.jet(),
+                Phase::Save => self.save_game(),
+                Phase::History => self.history(),
+                Phase::Offer(offer) => self.answer_offer(offer),
+                Phase::Fight => self.fight(),
+                Phase::GameOver => {
+                    self.you_win();
+                    break;
+                }
+            };
+        }
+    }
+
+    // up to max, which m or max fills in, an amount given up on is 0, the
+    // same as no amount. uncapped amounts over max are left to the engine
+    fn read_amount(&mut self, max: i64, capped: bool) -> i64 {
+        let max = max.max(0);
+        let mut text = String::new();
+        loop {
+            let check = |reply: &str| Self::parse_amount(reply, max, capped);
+            match self.input.amount(&mut text, &check) {
+                Ok(amount) => {
+                    let amount = amount.unwrap_or(0);
+                    self.record(Input::Number(amount));
+                    return amount;
+                }
+                Err(e) if is_resize(&e) => self.redraw(),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted && self.playing => self.pause(),
+                Err(e) => self.input_failed(e),
+            }
+        }
+    }
+
+    // 250, 1,000, 1.5k, 1e3, m or max, grouped & pointed the locale's way
+    fn parse_amount(reply: &str, max: i64, capped: bool) -> Result<i64, String> {
+        let locale = locale();
+        let reply = reply
+            .trim()
+            .to_lowercase()
+            .replace(&locale.thousands, "")
+            .replace(&locale.decimal, ".");
+        let amount = match reply.as_str() {
+            "" => return Ok(0),
+            "m" | "max" => return Ok(max),
+            reply => match reply.parse::<i64>() {
+                Ok(amount) => amount,
+                Err(_) => {
+                    let (number, scale) = match reply.strip_suffix('k') {
+                        Some(number) => (number, 1000.0),
+                        None => (reply, 1.0),
+                    };
+                    let amount = number
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|n| n.is_finite())
+                        .ok_or(t!("not_a_number"))?
+                        * scale;
+                    if amount.fract() != 0.0 {
+                        return Err(t!("not_whole").to_string());
+                    }
+                    if amount.abs() >= i64::MAX as f64 {
+                        return Err(t!("too_many").to_string());
                     }
+                    amount as i64
+                }
+            },
+        };
+        if amount < 0 {
+            Err(t!("negative").to_string())
+        } else if capped && amount > max {
+            Err(t!("no_more_than", max = Self::format_number(max)))
+        } else {
+            Ok(amount)
+        }
+    }
+
+    // the pause menu's keys never go in the journal
+    fn getch(&mut self) -> char {
+        loop {
+            match self.input.key() {
+                Ok(c) if self.pauses(c) => self.pause(),
+                Ok(c) => {
+                    self.record(Input::Key(c));
+                    return c;
                 }
+                Err(e) if is_resize(&e) => self.redraw(),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted && self.playing => self.pause(),
+                Err(e) => self.input_failed(e),
             }
         }
     }
 
+    // no drug goes by the pause key, the catalog was checked against it
+    fn pauses(&self, c: char) -> bool {
+        self.playing && (c == '\x1B' || c.to_ascii_lowercase() == key("pause"))
+    }
+
+    fn pause(&mut self) {
+        let screen = std::mem::replace(&mut *SCREEN.lock().unwrap(), Screen::blank());
+        loop {
+            self.pause_menu();
+            let reply = match self.input.live_key() {
+                Ok(c) => c.to_ascii_lowercase(),
+                Err(e) if is_resize(&e) => continue,
+                // a second ctrl-c walks away without a save or a score
+                Err(e) => self.input_failed(e),
+            };
+            if reply == key("resume") || reply == '\x1B' {
+                break;
+            } else if reply == key("save_quit") {
+                self.save_and_quit();
+            } else if reply == key("forfeit") {
+                emit!("\x1B[31m{}\x1B[0m ", t!("forfeit_confirm"));
+                io::stdout().flush().unwrap();
+                if self.pause_yn() {
+                    let _ = self.game.apply(Command::Forfeit);
+                    self.you_win();
+                    process::exit(0);
+                }
+            } else if reply == key("help") {
+                self.help();
+                let _ = self.input.live_key();
+            }
+        }
+        *SCREEN.lock().unwrap() = screen;
+        self.redraw();
+    }
+
+    fn pause_menu(&mut self) {
+        self.term_info();
+        clear_screen();
+        emitln!("\n\n");
+        self.centered("paused", "\x1B[1;32m");
+        emitln!();
+        for item in ["resume", "save_quit", "forfeit", "help"] {
+            emitln!("{}{}) {}", self.pad(7), hotkey(item), t!(item));
+        }
+        emitln!("\n");
+        io::stdout().flush().unwrap();
+    }
+
+    fn pause_yn(&mut self) -> bool {
+        loop {
+            match self.input.live_key() {
+                Ok(c) => return c.to_ascii_lowercase() == key("yes"),
+                Err(e) if is_resize(&e) => continue,
+                Err(e) => self.input_failed(e),
+            }
+        }
+    }
+
+    // quits only once the game is safely written
+    fn save_and_quit(&mut self) {
+        match &self.save_path {
+            Some(path) => match save::save(&self.game, path) {
+                Ok(()) => {
+                    emitln!("\x1B[32m{}\x1B[0m", t!("saved", path = path.display()));
+                    process::exit(0);
+                }
+                Err(e) => emit!("\x1B[31m{}\x1B[0m ", t!("cant_save", error = e)),
+            },
+            None => emit!("\x1B[31m{}\x1B[0m ", t!("nowhere_to_save")),
+        }
+        io::stdout().flush().unwrap();
+        let _ = self.input.live_key();
+    }
+
+    fn help(&mut self) {
+        self.term_info();
+        clear_screen();
+        emitln!("  \x1B[1;32m{}\x1B[0m\n", t!("help_title"));
+        for (heading, text) in Self::help_lines(&self.game) {
+            if heading.is_empty() {
+                emitln!("  {}", text);
+            } else {
+                emitln!("  \x1B[36m{:<15}\x1B[0m {}", heading, text);
+            }
+        }
+        emitln!("\n  \x1B[33m{}\x1B[0m", t!("hit_any_key"));
+        io::stdout().flush().unwrap();
+    }
+
+    // the help both clients show, a heading & its keys, or a line of its own
+    fn help_lines(game: &Game) -> Vec<(String, String)> {
+        let rules = game.rules();
+        let keys = |names: &[&str]| -> String {
+            let keys: Vec<String> =
+                names.iter().map(|name| format!("{}) {}", hotkey(name), t!(name))).collect();
+            keys.join("   ")
+        };
+        let line = |text: String| (String::new(), text);
+        let heading = |key: &str, text: String| (t!(key).to_string(), text);
+        vec![
+            line(t!("help_goal", days = rules.days)),
+            line(t!("help_interest", debt = rules.debt_interest, bank = rules.bank_interest)),
+            line(String::new()),
+            heading("at_the_market", keys(&["buy", "sell", "jet", "save", "history", "informant"])),
+            heading("buy_and_sell", t!("help_drug_key").to_string()),
+            heading("in_a_fight", keys(&["run", "fight"])),
+            heading("anywhere", t!("help_pause", pause = hotkey("pause"))),
+            line(String::new()),
+            line(t!("help_offered").to_string()),
+            line(t!("help_forfeit").to_string()),
+        ]
+    }
+
+    // ctrl-c out of a name or the too small screen, nothing is kept
+    fn input_failed(&mut self, e: io::Error) -> ! {
+        if e.kind() == io::ErrorKind::Interrupted {
+            emitln!();
+            process::exit(130);
+        }
+        eprintln!("\n{e}");
+        process::exit(1);
+    }
+
+    fn record(&mut self, input: Input) {
+        if let Some(journal) = self.journal.as_mut() {
+            let _ = journal.record(input);
+        }
+    }
+
     fn wait_for_key_press(&mut self) {
-        let _ = self.getch();
+        self.getch();
     }
 }
 
@@ -1454,7 +2390,477 @@ fn main() {
         process::exit(0);
     }
 
-    let mut game = GameState::new();
-    game.roll_prices();
-    game.start_game();
+    let mut load_path: Option<PathBuf> = None;
+    let mut seed: Option<u64> = None;
+    let mut journal_path: Option<PathBuf> = None;
+    let mut replay_path: Option<PathBuf> = None;
+    let mut step = false;
+    let mut script_path: Option<PathBuf> = None;
+    let mut catalog = Catalog::classic();
+    let mut map = Map::classic();
+    let mut events = Events::classic();
+    // any of the drugs, the map or the events swapped out
+    let mut modded = false;
+    let mut rules: Option<Rules> = None;
+    let mut difficulty: Option<Difficulty> = None;
+    let mut market: Option<Market> = None;
+    let mut limited = false;
+    let mut show_scores = false;
+    let mut player: Option<String> = None;
+    let mut full_screen = false;
+    let mut lang: Option<Locale> = None;
+    theme::set(theme::Theme::detect());
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--load" => match args.next() {
+                Some(path) => load_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--load needs a save file");
+                    process::exit(1);
+                }
+            },
+            "--seed" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(n) => seed = Some(n),
+                None => {
+                    eprintln!("--seed needs a number");
+                    process::exit(1);
+                }
+            },
+            "--journal" => match args.next() {
+                Some(path) => journal_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--journal needs a file");
+                    process::exit(1);
+                }
+            },
+            "--replay" => match args.next() {
+                Some(path) => replay_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--replay needs a journal file");
+                    process::exit(1);
+                }
+            },
+            "--step" => step = true,
+            "--script" => match args.next() {
+                Some(path) => script_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--script needs a file");
+                    process::exit(1);
+                }
+            },
+            "--drugs" => match args.next() {
+                Some(path) => {
+                    modded = true;
+                    catalog = Catalog::load(Path::new(path)).unwrap_or_else(|e| {
+                        eprintln!("couldn't load {path}: {e}");
+                        process::exit(1);
+                    })
+                }
+                None => {
+                    eprintln!("--drugs needs a drug catalog");
+                    process::exit(1);
+                }
+            },
+            "--map" => match args.next() {
+                Some(path) => {
+                    modded = true;
+                    map = Map::load(Path::new(path)).unwrap_or_else(|e| {
+                        eprintln!("couldn't load {path}: {e}");
+                        process::exit(1);
+                    })
+                }
+                None => {
+                    eprintln!("--map needs a map file");
+                    process::exit(1);
+                }
+            },
+            "--events" => match args.next() {
+                Some(path) => {
+                    modded = true;
+                    events = Events::load(Path::new(path)).unwrap_or_else(|e| {
+                        eprintln!("couldn't load {path}: {e}");
+                        process::exit(1);
+                    })
+                }
+                None => {
+                    eprintln!("--events needs an events file");
+                    process::exit(1);
+                }
+            },
+            "--difficulty" => match args.next().map(|name| name.parse::<Difficulty>()) {
+                Some(Ok(name)) => difficulty = Some(name),
+                Some(Err(e)) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+                None => {
+                    eprintln!("--difficulty needs easy, classic, hard or custom");
+                    process::exit(1);
+                }
+            },
+            "--rules" => match args.next() {
+                Some(path) => {
+                    rules = Some(Rules::load(Path::new(path)).unwrap_or_else(|e| {
+                        eprintln!("couldn't load {path}: {e}");
+                        process::exit(1);
+                    }))
+                }
+                None => {
+                    eprintln!("--rules needs a rules file");
+                    process::exit(1);
+                }
+            },
+            "--market" => match args.next().map(|name| name.parse::<Market>()) {
+                Some(Ok(name)) => market = Some(name),
+                Some(Err(e)) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+                None => {
+                    eprintln!("--market needs classic or drifting");
+                    process::exit(1);
+                }
+            },
+            "--limited" => limited = true,
+            "--scores" => show_scores = true,
+            "--profile" => match args.next() {
+                Some(name) if !name.trim().is_empty() => {
+                    player = Some(name.trim().to_uppercase().chars().take(12).collect())
+                }
+                _ => {
+                    eprintln!("--profile needs a player name");
+                    process::exit(1);
+                }
+            },
+            "--plain" => PLAIN.store(true, AtomicOrdering::Relaxed),
+            "--theme" => match args.next().and_then(|name| theme::Theme::from_name(name)) {
+                Some(theme) => theme::set(theme),
+                None => {
+                    eprintln!("--theme needs {}", theme::NAMES);
+                    process::exit(1);
+                }
+            },
+            "--screen-reader" => READER.store(true, AtomicOrdering::Relaxed),
+            "--tui" => full_screen = true,
+            // a language we have, or a locale file of your own
+            "--lang" => match args.next() {
+                Some(name) if name.ends_with(".toml") => match Locale::load(Path::new(name)) {
+                    Ok(locale) => lang = Some(locale),
+                    Err(e) => {
+                        eprintln!("couldn't load {name}: {e}");
+                        process::exit(1);
+                    }
+                },
+                Some(name) => match Locale::built_in(name) {
+                    Some(locale) => lang = Some(locale),
+                    None => {
+                        eprintln!("--lang needs {} or a locale file", locale::NAMES);
+                        process::exit(1);
+                    }
+                },
+                None => {
+                    eprintln!("--lang needs a language or a locale file");
+                    process::exit(1);
+                }
+            },
+            _ => {
+                eprintln!("unknown argument: {arg}");
+                process::exit(1);
+            }
+        }
+    }
+    let _ = LOCALE.set(lang.unwrap_or_else(Locale::from_env));
+
+    if show_scores {
+        let scores = highscores::default_path()
+            .map(|path| highscores::load(&path))
+            .transpose()
+            .unwrap_or_else(|e| {
+                eprintln!("couldn't read the high scores: {e}");
+                process::exit(1);
+            })
+            .unwrap_or_default();
+        for line in Client::leaderboard(&scores, difficulty, SortBy::Money) {
+            println!("{line}");
+        }
+        process::exit(0);
+    }
+    let difficulty = difficulty.unwrap_or(Difficulty::Classic);
+
+    // no terminal on stdin, or a script, means a headless game that stays
+    // out of the player's save, journal, scores & profiles unless asked
+    let input: Box<dyn InputSource> = if let Some(path) = &script_path {
+        let file = File::open(path).unwrap_or_else(|e| {
+            eprintln!("couldn't open {}: {e}", path.display());
+            process::exit(1);
+        });
+        Box::new(Script::new(BufReader::new(file)))
+    } else if !io::stdin().is_terminal() {
+        Box::new(Script::new(io::stdin().lock()))
+    } else {
+        Box::new(Terminal)
+    };
+    let headless = script_path.is_some() || !io::stdin().is_terminal();
+    if headless {
+        PLAIN.store(true, AtomicOrdering::Relaxed);
+    } else {
+        term::install();
+        if journal_path.is_none() {
+            journal_path = journal::default_path();
+        }
+    }
+
+    // a rules file makes the game custom, custom needs a rules file
+    let rules = match rules {
+        Some(rules) if matches!(difficulty, Difficulty::Classic | Difficulty::Custom) => rules,
+        Some(_) => {
+            eprintln!("--rules can't be mixed with --difficulty {difficulty}");
+            process::exit(1);
+        }
+        None => Rules::preset(difficulty).unwrap_or_else(|| {
+            eprintln!("--difficulty custom needs --rules <file>");
+            process::exit(1);
+        }),
+    };
+
+    // a market other than the rules' own is custom too, & so are limited
+    // drugs when the rules don't limit them, & other drugs, maps or events
+    let mut rules = rules;
+    if modded {
+        rules.difficulty = Difficulty::Custom;
+    }
+    if let Some(market) = market.filter(|market| *market != rules.market) {
+        rules.market = market;
+        rules.difficulty = Difficulty::Custom;
+    }
+    if limited && !rules.limited {
+        rules.limited = true;
+        rules.difficulty = Difficulty::Custom;
+    }
+
+    // the language may give the drugs other keys, none of them the pause key
+    if let Err(e) = catalog.localize(locale()) {
+        eprintln!("the drugs don't fit the language: {e}");
+        process::exit(1);
+    }
+
+    let setup = Setup::new(rules, catalog, map, events).unwrap_or_else(|e| {
+        eprintln!("the map doesn't fit the drugs: {e}");
+        process::exit(1);
+    });
+
+    // --profile picks a player & remembers them, otherwise it's whoever
+    // played last, a headless game only borrows the name
+    let player = match (profile::default_path().filter(|_| !headless), player) {
+        (Some(path), Some(name)) => match profile::select(&path, &name) {
+            Ok(profile) => Some(profile.name),
+            Err(e) => {
+                eprintln!("couldn't pick profile {name}: {e}");
+                process::exit(1);
+            }
+        },
+        (None, player) => player,
+        (Some(path), None) => profile::load(&path).ok().and_then(|profiles| profiles.last),
+    };
+
+    if full_screen {
+        if headless || replay_path.is_some() {
+            eprintln!("--tui needs a terminal & can't replay a journal");
+            process::exit(1);
+        }
+        if READER.load(AtomicOrdering::Relaxed) {
+            eprintln!("--tui draws a full screen, play with --screen-reader alone");
+            process::exit(1);
+        }
+        let (game, save_path, fresh) = match load_path {
+            Some(path) => {
+                let game = load_game(&path).unwrap_or_else(|e| {
+                    eprintln!("couldn't load {}: {e}", path.display());
+                    process::exit(1);
+                });
+                (game, Some(path), false)
+            }
+            None => {
+                let game = Game::with_setup(seed.unwrap_or_else(rand::random), setup);
+                (game, save::default_path(), true)
+            }
+        };
+        play_tui(game, save_path, player, fresh);
+        return;
+    }
+    watch_resize();
+
+    if let Some(path) = replay_path {
+        let journal = journal::load(&path).unwrap_or_else(|e| {
+            eprintln!("couldn't load {}: {e}", path.display());
+            process::exit(1);
+        });
+        // the journal's drugs keep the keys they were played with
+        if let Err(e) = journal.setup.catalog.check_keys(key("pause")) {
+            eprintln!("the journal's drugs don't fit the language: {e}");
+            process::exit(1);
+        }
+        let replay = Replay {
+            inputs: journal.inputs,
+            step,
+            terminal: Terminal,
+        };
+        let mut client = Client::new(
+            Game::with_setup(journal.seed, journal.setup),
+            None,
+            Box::new(replay),
+        );
+        client.keep_score = false;
+        client.run(Phase::Title);
+        return;
+    }
+
+    match load_path {
+        Some(path) => {
+            let game = load_game(&path).unwrap_or_else(|e| {
+                eprintln!("couldn't load {}: {e}", path.display());
+                process::exit(1);
+            });
+            let save_path = if headless { None } else { Some(path) };
+            let mut client = Client::new(game, save_path, input);
+            client.player = player;
+            client.keep_score = !headless;
+            client.run(Phase::Market);
+        }
+        None => {
+            let seed = seed.unwrap_or_else(rand::random);
+            let journal =
+                journal_path.and_then(|path| JournalWriter::create(&path, seed, &setup).ok());
+            let game = Game::with_setup(seed, setup);
+            let save_path = if headless { None } else { save::default_path() };
+            let mut client = Client::new(game, save_path, input);
+            client.journal = journal;
+            client.player = player;
+            client.keep_score = !headless;
+            client.run(Phase::Title);
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn play_tui(game: Game, save_path: Option<PathBuf>, player: Option<String>, fresh: bool) {
+    if let Err(e) = tui::run(game, save_path, player, fresh) {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn play_tui(_game: Game, _save_path: Option<PathBuf>, _player: Option<String>, _fresh: bool) {
+    eprintln!("--tui needs a build with --features tui");
+    process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_read_like_people_type_them() {
+        let parse = |reply| Client::parse_amount(reply, 5000, false);
+        assert_eq!(parse(""), Ok(0));
+        assert_eq!(parse("250"), Ok(250));
+        assert_eq!(parse("1,000"), Ok(1000));
+        assert_eq!(parse("1.5k"), Ok(1500));
+        assert_eq!(parse("2K"), Ok(2000));
+        assert_eq!(parse("1e3"), Ok(1000));
+        assert_eq!(parse("m"), Ok(5000));
+        assert_eq!(parse("MAX"), Ok(5000));
+    }
+
+    #[test]
+    fn amounts_that_make_no_sense_are_turned_away() {
+        let parse = |reply| Client::parse_amount(reply, 5000, true);
+        assert!(parse("lots").is_err());
+        assert!(parse("1.5").is_err());
+        assert!(parse("-3").is_err());
+        assert!(parse("1e30").is_err());
+        assert!(parse("inf").is_err());
+        assert!(parse("5001").is_err());
+        assert_eq!(parse("5000"), Ok(5000));
+    }
+
+    // a client of any size, without asking the terminal for one
+    fn sized(width: usize, height: usize, setup: Setup) -> Client {
+        Client {
+            game: Game::with_setup(7, setup),
+            arrival: Phase::Market,
+            save_path: None,
+            input: Box::new(Script::new(io::empty())),
+            journal: None,
+            keep_score: false,
+            player: None,
+            banner: None,
+            playing: false,
+            read_out: String::new(),
+            width,
+            height,
+            wid: width / 2,
+        }
+    }
+
+    #[test]
+    fn the_window_fits_the_hud_and_a_prompt() {
+        assert!(sized(80, 24, Setup::default()).fits());
+        assert!(sized(MIN_WIDTH, 20, Setup::default()).fits());
+        assert!(!sized(MIN_WIDTH - 1, 24, Setup::default()).fits());
+        assert!(!sized(80, 19, Setup::default()).fits());
+
+        // every drug takes a line of the hud
+        let drugs = include_str!("../data/drugs.toml").to_string()
+            + "[[drug]]\nname = \"GLUE\"\nkey = \"g\"\nmin_price = 10\nmax_price = 50\n";
+        let glue = Setup {
+            catalog: Catalog::from_toml(&drugs).unwrap(),
+            ..Setup::default()
+        };
+        assert!(!sized(80, 20, glue.clone()).fits());
+        assert!(sized(80, 21, glue).fits());
+    }
+
+    #[test]
+    fn text_is_centered_by_its_letters() {
+        let client = sized(80, 24, Setup::default());
+        assert_eq!(client.center("PAUSED").len(), 37);
+        assert_eq!(client.center("AÑO").len(), 38);
+        assert_eq!(sized(4, 24, Setup::default()).center("PAUSED"), "");
+    }
+
+    // the only test here that touches the data dir, so it has it to itself
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn a_forfeit_never_makes_the_high_scores() {
+        let dir = env::temp_dir().join(format!("drugwars-forfeit-{}", process::id()));
+        env::set_var("XDG_DATA_HOME", &dir);
+        let one_day = Setup {
+            rules: Rules {
+                days: 1,
+                ..Rules::classic()
+            },
+            ..Setup::default()
+        };
+
+        let mut game = Game::with_setup(7, one_day.clone());
+        game.apply(Command::Forfeit).unwrap();
+        let (scores, errors) = Client::record_score(&game, "QUITTER".to_string(), true);
+        assert!(errors.is_empty());
+        assert!(scores.iter().all(|score| score.name != "QUITTER"));
+        assert!(!highscores::default_path().unwrap().exists());
+        // the profile still counts the game
+        let profiles = profile::load(&profile::default_path().unwrap()).unwrap();
+        assert_eq!(profiles.find("QUITTER").unwrap().games, 1);
+
+        let mut game = Game::with_setup(7, one_day);
+        let ghetto = game.map().from_key('2').unwrap();
+        game.apply(Command::Jet(ghetto)).unwrap();
+        assert_eq!(game.end(), Some(End::Survived));
+        let (scores, _) = Client::record_score(&game, "STAYER".to_string(), true);
+        assert!(scores.iter().any(|score| score.name == "STAYER"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
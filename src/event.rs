@@ -0,0 +1,63 @@
+// event.rs 🦀
+
+use serde::{Deserialize, Serialize};
+
+use crate::achievement::Achievement;
+use crate::drug::Drug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    // an entry of the events table, n is what its find, lose or cash rolled
+    Random {
+        id: usize,
+        drug: Option<Drug>,
+        n: i64,
+    },
+    Hallucinated,
+    CopsChasing {
+        cops: i64,
+    },
+    LostThem,
+    YouFire,
+    YouMissed,
+    KilledOne,
+    KilledAll {
+        reward: i64,
+    },
+    CopsFire,
+    CopsMissed,
+    Hit {
+        damage: i64,
+    },
+    Wasted,
+    Unlocked(Achievement),
+    GameOver(End),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offer {
+    Paraquat,
+    Gun { gun: &'static str, price: i64 },
+    Coat { space: i64, price: i64 },
+    Doctor { cost: i64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum End {
+    Survived,
+    Wasted,
+    Paraquat,
+    // given up from the pause menu, kept off the high scores
+    Forfeit,
+}
+
+impl End {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            End::Survived => "SURVIVED",
+            End::Wasted => "WASTED BY COPS",
+            End::Paraquat => "PARAQUAT",
+            End::Forfeit => "FORFEIT",
+        }
+    }
+}
@@ -0,0 +1,214 @@
+// events.rs 🦀
+// the random events of the day, the classic ones live in data/events.toml
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+static CLASSIC: &str = include_str!("../data/events.toml");
+
+fn one() -> u32 {
+    1
+}
+
+fn one_i64() -> i64 {
+    1
+}
+
+fn magenta() -> String {
+    "magenta".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OfferKind {
+    Paraquat,
+    Gun,
+    Coat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventSpec {
+    pub name: String,
+    #[serde(default)]
+    pub messages: Vec<String>,
+    #[serde(default = "magenta")]
+    pub color: String,
+    #[serde(default = "one")]
+    pub weight: u32,
+    #[serde(default)]
+    pub chance: Option<u32>,
+
+    #[serde(default)]
+    pub drug: Option<String>,
+    #[serde(default)]
+    pub locations: Option<Vec<String>>,
+    #[serde(default)]
+    pub min_day: Option<i64>,
+    #[serde(default)]
+    pub max_day: Option<i64>,
+    #[serde(default)]
+    pub min_cash: Option<i64>,
+    #[serde(default)]
+    pub max_cash: Option<i64>,
+    #[serde(default)]
+    pub min_space: Option<i64>,
+    #[serde(default)]
+    pub max_space: Option<i64>,
+    #[serde(default)]
+    pub min_amount: i64,
+
+    #[serde(default = "one_i64")]
+    pub multiply: i64,
+    #[serde(default = "one_i64")]
+    pub divide: i64,
+    #[serde(default)]
+    pub lose: Option<[i64; 2]>,
+    #[serde(default)]
+    pub find: Option<[i64; 2]>,
+    #[serde(default)]
+    pub cash_percent: Option<i64>,
+    #[serde(default)]
+    pub cash: Option<[i64; 2]>,
+    #[serde(default)]
+    pub offer: Option<OfferKind>,
+    #[serde(default)]
+    pub price: Option<[i64; 2]>,
+    #[serde(default)]
+    pub space: Option<[i64; 2]>,
+}
+
+impl EventSpec {
+    pub fn changes_price(&self) -> bool {
+        self.multiply != 1 || self.divide != 1
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Events {
+    #[serde(default = "one")]
+    pub per_day: u32,
+    #[serde(default, rename = "event")]
+    events: Vec<EventSpec>,
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Events {
+    pub fn classic() -> Self {
+        Self::from_toml(CLASSIC).unwrap()
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let events: Events = toml::from_str(text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        events.validate()?;
+        Ok(events)
+    }
+
+    pub(crate) fn validate(&self) -> io::Result<()> {
+        let bad = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        let backwards = |range: Option<[i64; 2]>| range.is_some_and(|[lo, hi]| lo > hi);
+
+        for spec in &self.events {
+            if spec.chance.is_some_and(|chance| chance > 100) {
+                return bad(format!("{}: chance is a percent", spec.name));
+            }
+            if spec.multiply <= 0 || spec.divide <= 0 {
+                return bad(format!("{}: multiply & divide must be positive", spec.name));
+            }
+            if spec.drug.is_none() && (spec.changes_price() || spec.min_amount > 0) {
+                return bad(format!("{}: needs a drug", spec.name));
+            }
+            if [spec.lose, spec.find, spec.cash, spec.price, spec.space]
+                .into_iter()
+                .any(backwards)
+            {
+                return bad(format!("{}: ranges go from low to high", spec.name));
+            }
+            if spec.lose.is_some_and(|[lo, _]| lo < 1) {
+                return bad(format!("{}: lose starts at 1", spec.name));
+            }
+            if spec.find.is_some_and(|[lo, _]| lo < 0) || spec.cash_percent.is_some_and(|p| p < 0) {
+                return bad(format!(
+                    "{}: find & cash_percent can't be negative",
+                    spec.name
+                ));
+            }
+            if spec.lose.is_some() && spec.find.is_some() {
+                return bad(format!("{}: can't both lose & find drugs", spec.name));
+            }
+            match spec.offer {
+                Some(OfferKind::Gun) if spec.price.is_none() => {
+                    return bad(format!("{}: a gun needs a price", spec.name));
+                }
+                Some(OfferKind::Coat) if spec.price.is_none() || spec.space.is_none() => {
+                    return bad(format!("{}: a coat needs a price & space", spec.name));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn spec(&self, id: usize) -> &EventSpec {
+        &self.events[id]
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.events.iter().position(|spec| spec.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one(line: &str) -> io::Result<Events> {
+        Events::from_toml(&format!("[[event]]\nname = \"TEST\"\n{line}\n"))
+    }
+
+    #[test]
+    fn the_classic_events_load() {
+        let events = Events::classic();
+        assert_eq!(events.per_day, 1);
+        assert!(!events.is_empty());
+        let spec = events.spec(events.find("mugged").unwrap());
+        assert_eq!(spec.name, "mugged");
+        assert!(Events::from_toml("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn bad_events_are_turned_away() {
+        assert!(one("drug = \"WEED\"\nmultiply = 2").is_ok());
+        assert!(one("chance = 101").is_err());
+        assert!(one("drug = \"WEED\"\ndivide = 0").is_err());
+        assert!(one("multiply = 2").is_err());
+        assert!(one("min_amount = 1").is_err());
+        assert!(one("cash = [100, -100]").is_err());
+        assert!(one("lose = [0, 2]").is_err());
+        assert!(one("find = [-1, 2]").is_err());
+        assert!(one("cash_percent = -1").is_err());
+        assert!(one("lose = [1, 2]\nfind = [1, 2]").is_err());
+        assert!(one("offer = \"gun\"").is_err());
+        assert!(one("offer = \"coat\"\nprice = [1, 2]").is_err());
+        assert!(one("offer = \"wife\"").is_err());
+    }
+}
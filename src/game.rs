@@ -0,0 +1,1252 @@
+// game.rs 🦀
+// the rules of the game, no terminal io in here
+
+use rand::distributions::WeightedIndex;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+use crate::achievement::Achievement;
+use crate::catalog::Catalog;
+use crate::drug::Drug;
+use crate::event::{End, Event, Offer};
+use crate::events::{EventSpec, Events, OfferKind};
+use crate::history::History;
+use crate::location::Location;
+use crate::map::{LocationSpec, Map};
+use crate::market::{Prices, Sighting, Stock};
+use crate::rules::Rules;
+use crate::score::Score;
+use crate::setup::Setup;
+use crate::stats::Stats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Buy { drug: Drug, amount: i64 },
+    Sell { drug: Drug, amount: i64 },
+    Jet(Location),
+    Repay(i64),
+    Borrow(i64),
+    // drop in on the loan shark, just looking is enough to lose LONE WOLF
+    LoanShark,
+    Deposit(i64),
+    Withdraw(i64),
+    Stash { drug: Drug, amount: i64 },
+    Take { drug: Drug, amount: i64 },
+    // pay the informant for tomorrow's prices somewhere else
+    Tip(Location),
+    Run,
+    Fight,
+    Accept,
+    Decline,
+    Forfeit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    GameOver,
+    InFight,
+    NotInFight,
+    OfferPending,
+    NoOffer,
+    NotHere,
+    NotSold,
+    BadAmount,
+    CantAfford,
+    NoRoom,
+    NotEnough,
+    MoreThanOwed,
+    LoanTooBig,
+    NoGuns,
+    AlreadyThere,
+    NoMoreTips,
+    NoneToday,
+    ShortSupply,
+    NoBuyers,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Error::GameOver => "the game is over",
+            Error::InFight => "the cops are chasing you",
+            Error::NotInFight => "nobody is chasing you",
+            Error::OfferPending => "an offer is waiting for an answer",
+            Error::NoOffer => "there is no offer to answer",
+            Error::NotHere => "you can't do that here",
+            Error::NotSold => "nobody deals that here",
+            Error::BadAmount => "amount must be positive",
+            Error::CantAfford => "not enough cash",
+            Error::NoRoom => "not enough room in your trench coat",
+            Error::NotEnough => "you don't have that many",
+            Error::MoreThanOwed => "that is more than you owe",
+            Error::LoanTooBig => "the loan shark won't lend you that much",
+            Error::NoGuns => "you have no guns",
+            Error::AlreadyThere => "you're already there",
+            Error::NoMoreTips => "the informant has nothing more today",
+            Error::NoneToday => "there's none of that here today",
+            Error::ShortSupply => "they don't have that many",
+            Error::NoBuyers => "they won't buy that many",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Serialize, Deserialize)]
+pub struct Game {
+    day: i64,
+    days_left: i64,
+    location: Location,
+    cash: i64,
+    guns: i64,
+    bank: i64,
+    debt: i64,
+    maxloan: i64,
+    hold: i64,
+    rules: Rules,
+    catalog: Catalog,
+    map: Map,
+    events: Events,
+    stash: Vec<i64>,
+    trench_coat: Vec<i64>,
+    damage: i64,
+    cops: i64,
+    prices: Prices,
+    // the last prices seen in every place, by location
+    seen: Vec<Option<Sighting>>,
+    // every price seen where you were, day by day
+    history: History,
+    #[serde(skip)]
+    offer: Option<Offer>,
+    fight_due: bool,
+    end: Option<End>,
+    peak: i64,
+    stats: Stats,
+    achievements: Vec<Achievement>,
+    used_loan_shark: bool,
+    seed: u64,
+    // saved mid stream, so a save & load plays on exactly like the game would have
+    rng: ChaCha12Rng,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_setup(seed, Setup::default())
+    }
+
+    pub fn with_setup(seed: u64, setup: Setup) -> Self {
+        let Setup {
+            rules,
+            catalog,
+            map,
+            events,
+        } = setup;
+        let drugs = catalog.len();
+        let mut stats = Stats::new(drugs, map.len());
+        stats.visits[map.start().0] += 1;
+        let prices = Prices::new(rules.market, rules.limited, map.len(), drugs);
+        let seen = vec![None; map.len()];
+        let mut game = Game {
+            day: 0,
+            days_left: rules.days,
+            location: map.start(),
+            cash: rules.cash,
+            guns: 0,
+            bank: 0,
+            debt: rules.debt,
+            maxloan: rules.max_loan,
+            hold: rules.hold,
+            rules,
+            catalog,
+            map,
+            events,
+            stash: vec![0; drugs],
+            trench_coat: vec![0; drugs],
+            damage: 0,
+            cops: 0,
+            prices,
+            seen,
+            history: History::new(),
+            offer: None,
+            fight_due: false,
+            end: None,
+            peak: 0,
+            stats,
+            achievements: Vec::new(),
+            used_loan_shark: false,
+            seed,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        };
+
+        game.prices
+            .open(game.location, &game.map, &game.catalog, &mut game.rng);
+        game.peak = game.net_worth();
+        game
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn day(&self) -> i64 {
+        self.day
+    }
+
+    pub fn days_left(&self) -> i64 {
+        self.days_left
+    }
+
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    pub fn cash(&self) -> i64 {
+        self.cash
+    }
+
+    pub fn guns(&self) -> i64 {
+        self.guns
+    }
+
+    pub fn bank(&self) -> i64 {
+        self.bank
+    }
+
+    pub fn debt(&self) -> i64 {
+        self.debt
+    }
+
+    pub fn max_loan(&self) -> i64 {
+        self.maxloan
+    }
+
+    pub fn hold(&self) -> i64 {
+        self.hold
+    }
+
+    pub fn damage(&self) -> i64 {
+        self.damage
+    }
+
+    pub fn cops(&self) -> i64 {
+        self.cops
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    pub fn map(&self) -> &Map {
+        &self.map
+    }
+
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+
+    pub fn here(&self) -> &LocationSpec {
+        self.map.spec(self.location)
+    }
+
+    // dealt here & around today
+    pub fn sold_here(&self, drug: Drug) -> bool {
+        self.deals_here(drug).is_ok()
+    }
+
+    fn deals_here(&self, drug: Drug) -> Result<(), Error> {
+        if !self.map.sells(self.location, &self.catalog, drug) {
+            return Err(Error::NotSold);
+        }
+        if !self.prices.has(self.location, drug) {
+            return Err(Error::NoneToday);
+        }
+        Ok(())
+    }
+
+    // dealt here, just not today
+    pub fn none_today(&self, drug: Drug) -> bool {
+        self.deals_here(drug) == Err(Error::NoneToday)
+    }
+
+    // what's left to buy & sell here today, none when drugs aren't limited
+    pub fn stock(&self, drug: Drug) -> Option<Stock> {
+        self.prices.stock(self.location, drug)
+    }
+
+    pub fn stash(&self, drug: Drug) -> i64 {
+        self.stash[drug.0]
+    }
+
+    pub fn trench_coat(&self, drug: Drug) -> i64 {
+        self.trench_coat[drug.0]
+    }
+
+    pub fn price(&self, drug: Drug) -> i64 {
+        self.prices.get(self.location, drug)
+    }
+
+    pub fn prices(&self) -> &Prices {
+        &self.prices
+    }
+
+    // what the prices were when you were last in a place, or what the
+    // informant said they'll be
+    pub fn seen(&self, location: Location) -> Option<&Sighting> {
+        self.seen[location.0].as_ref()
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn afford(&self, drug: Drug) -> i64 {
+        self.prices.afford(self.location, drug, self.cash)
+    }
+
+    pub fn offer(&self) -> Option<Offer> {
+        self.offer
+    }
+
+    pub fn in_fight(&self) -> bool {
+        self.cops > 0
+    }
+
+    pub fn end(&self) -> Option<End> {
+        self.end
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.end.is_some()
+    }
+
+    // a loaded game is only as good as its file, everything in it has to
+    // fit the drugs & the map it came with
+    pub(crate) fn validate(&self) -> io::Result<()> {
+        let bad = |msg: &str| Err(io::Error::new(io::ErrorKind::InvalidData, msg.to_string()));
+
+        self.rules.validate()?;
+        self.catalog.validate()?;
+        self.map.validate()?;
+        self.events.validate()?;
+        self.map.check(&self.catalog)?;
+        let (locations, drugs) = (self.map.len(), self.catalog.len());
+        if self.location.0 >= locations {
+            return bad("the game is somewhere off the map");
+        }
+        if self.stash.len() != drugs || self.trench_coat.len() != drugs {
+            return bad("the stash & the trench coat don't fit the drugs");
+        }
+        if !self.prices.fits(locations, drugs) {
+            return bad("the prices don't fit the drugs & the map");
+        }
+        let seen =
+            self.seen.len() == locations && self.seen.iter().flatten().all(|seen| seen.fits(drugs));
+        if !seen || !self.history.fits(locations, drugs) {
+            return bad("the prices seen don't fit the drugs & the map");
+        }
+        let stats = &self.stats;
+        if stats.bought.len() != drugs
+            || stats.sold.len() != drugs
+            || stats.visits.len() != locations
+        {
+            return bad("the stats don't fit the drugs & the map");
+        }
+        Ok(())
+    }
+
+    // offers, fights and finished games don't go in a save file
+    pub fn can_save(&self) -> bool {
+        self.offer.is_none() && !self.in_fight() && !self.is_over()
+    }
+
+    pub fn net_worth(&self) -> i64 {
+        self.bank
+            .saturating_add(self.cash)
+            .saturating_sub(self.debt)
+    }
+
+    // the most the player was ever worth, checked after every command
+    pub fn peak_net_worth(&self) -> i64 {
+        self.peak
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    // unlocked this game, in the order they happened
+    pub fn achievements(&self) -> &[Achievement] {
+        &self.achievements
+    }
+
+    pub fn score(&self) -> Score {
+        Score::new(self.net_worth())
+    }
+
+    // the first day rolls an event but never a fight
+    pub fn start(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.roll_events(&mut events);
+        self.peak = self.peak.max(self.net_worth());
+        self.note_prices();
+        events
+    }
+
+    pub fn apply(&mut self, command: Command) -> Result<Vec<Event>, Error> {
+        if self.end.is_some() {
+            return Err(Error::GameOver);
+        }
+        match command {
+            Command::Accept | Command::Decline | Command::Forfeit => {}
+            _ if self.offer.is_some() => return Err(Error::OfferPending),
+            Command::Run | Command::Fight => {}
+            _ if self.in_fight() => return Err(Error::InFight),
+            _ => {}
+        }
+
+        let mut events = Vec::new();
+        match command {
+            Command::Buy { drug, amount } => self.buy(drug, amount)?,
+            Command::Sell { drug, amount } => self.sell(drug, amount)?,
+            Command::Jet(location) => self.jet(location, &mut events),
+            Command::Repay(amount) => {
+                self.repay(amount)?;
+                if self.debt == 0 {
+                    self.unlock(Achievement::DebtFree, &mut events);
+                }
+            }
+            Command::Borrow(amount) => self.borrow(amount)?,
+            Command::LoanShark => self.visit_loan_shark()?,
+            Command::Deposit(amount) => self.deposit(amount)?,
+            Command::Withdraw(amount) => self.withdraw(amount)?,
+            Command::Stash { drug, amount } => self.stash_drug(drug, amount)?,
+            Command::Take { drug, amount } => self.take_drug(drug, amount)?,
+            Command::Tip(location) => self.tip(location)?,
+            Command::Run => self.run(&mut events)?,
+            Command::Fight => self.fight(&mut events)?,
+            Command::Accept => self.answer(true, &mut events)?,
+            Command::Decline => self.answer(false, &mut events)?,
+            Command::Forfeit => self.game_over(End::Forfeit, &mut events),
+        }
+        self.peak = self.peak.max(self.net_worth());
+        self.note_prices();
+        Ok(events)
+    }
+
+    // whatever the prices here are now, after the deals & events
+    fn note_prices(&mut self) {
+        if self.end.is_none() {
+            let prices = self.prices.row(self.location);
+            let sighting = Sighting {
+                day: self.day,
+                prices,
+            };
+            self.history.record(self.location, sighting);
+        }
+    }
+
+    fn buy(&mut self, drug: Drug, amount: i64) -> Result<(), Error> {
+        self.deals_here(drug)?;
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if self.stock(drug).is_some_and(|stock| amount > stock.supply) {
+            return Err(Error::ShortSupply);
+        }
+        if amount > self.afford(drug) {
+            return Err(Error::CantAfford);
+        }
+        if self.hold - amount < 0 {
+            return Err(Error::NoRoom);
+        }
+        self.trench_coat[drug.0] += amount;
+        self.cash -= self.prices.cost(self.location, drug, amount);
+        self.prices.deal(self.location, drug, amount);
+        self.hold -= amount;
+        self.stats.bought[drug.0] += amount;
+        Ok(())
+    }
+
+    fn sell(&mut self, drug: Drug, amount: i64) -> Result<(), Error> {
+        self.deals_here(drug)?;
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.trench_coat(drug) {
+            return Err(Error::NotEnough);
+        }
+        if self.stock(drug).is_some_and(|stock| amount > stock.demand) {
+            return Err(Error::NoBuyers);
+        }
+        self.trench_coat[drug.0] -= amount;
+        self.cash = self
+            .cash
+            .saturating_add(self.prices.takings(self.location, drug, amount));
+        self.prices.deal(self.location, drug, -amount);
+        self.hold += amount;
+        self.stats.sold[drug.0] += amount;
+        Ok(())
+    }
+
+    fn jet(&mut self, location: Location, events: &mut Vec<Event>) {
+        self.seen[self.location.0] = Some(Sighting {
+            day: self.day,
+            prices: self.prices.row(self.location),
+        });
+        self.location = location;
+        self.days_left -= 1;
+        if self.days_left <= 0 {
+            self.game_over(End::Survived, events);
+            return;
+        }
+        self.day += 1;
+        self.stats.visits[location.0] += 1;
+        self.prices
+            .next_day(location, &self.map, &self.catalog, &mut self.rng);
+        self.debt = with_interest(self.debt, self.rules.debt_interest);
+        self.bank = with_interest(self.bank, self.rules.bank_interest);
+        self.fight_due = true;
+        self.roll_events(events);
+        if self.offer.is_none() {
+            self.roll_fight(events);
+        }
+    }
+
+    fn repay(&mut self, amount: i64) -> Result<(), Error> {
+        if !self.here().loan_shark {
+            return Err(Error::NotHere);
+        }
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.cash {
+            return Err(Error::CantAfford);
+        }
+        if amount > self.debt {
+            return Err(Error::MoreThanOwed);
+        }
+        self.cash -= amount;
+        self.debt -= amount;
+        self.used_loan_shark = true;
+        Ok(())
+    }
+
+    fn borrow(&mut self, amount: i64) -> Result<(), Error> {
+        if !self.here().loan_shark {
+            return Err(Error::NotHere);
+        }
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.maxloan.saturating_sub(self.debt) {
+            return Err(Error::LoanTooBig);
+        }
+        self.debt += amount;
+        self.cash = self.cash.saturating_add(amount);
+        self.used_loan_shark = true;
+        Ok(())
+    }
+
+    fn visit_loan_shark(&mut self) -> Result<(), Error> {
+        if !self.here().loan_shark {
+            return Err(Error::NotHere);
+        }
+        self.used_loan_shark = true;
+        Ok(())
+    }
+
+    fn deposit(&mut self, amount: i64) -> Result<(), Error> {
+        if !self.here().bank {
+            return Err(Error::NotHere);
+        }
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.cash {
+            return Err(Error::CantAfford);
+        }
+        self.bank = self.bank.saturating_add(amount);
+        self.cash -= amount;
+        Ok(())
+    }
+
+    fn withdraw(&mut self, amount: i64) -> Result<(), Error> {
+        if !self.here().bank {
+            return Err(Error::NotHere);
+        }
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.bank {
+            return Err(Error::NotEnough);
+        }
+        self.bank -= amount;
+        self.cash = self.cash.saturating_add(amount);
+        Ok(())
+    }
+
+    fn stash_drug(&mut self, drug: Drug, amount: i64) -> Result<(), Error> {
+        if !self.here().stash {
+            return Err(Error::NotHere);
+        }
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.trench_coat(drug) {
+            return Err(Error::NotEnough);
+        }
+        self.trench_coat[drug.0] -= amount;
+        self.stash[drug.0] += amount;
+        self.hold += amount;
+        Ok(())
+    }
+
+    fn take_drug(&mut self, drug: Drug, amount: i64) -> Result<(), Error> {
+        if !self.here().stash {
+            return Err(Error::NotHere);
+        }
+        if amount <= 0 {
+            return Err(Error::BadAmount);
+        }
+        if amount > self.stash(drug) {
+            return Err(Error::NotEnough);
+        }
+        if self.hold - amount < 0 {
+            return Err(Error::NoRoom);
+        }
+        self.stash[drug.0] -= amount;
+        self.trench_coat[drug.0] += amount;
+        self.hold -= amount;
+        Ok(())
+    }
+
+    fn tip(&mut self, location: Location) -> Result<(), Error> {
+        if !self.here().informant {
+            return Err(Error::NotHere);
+        }
+        if location == self.location {
+            return Err(Error::AlreadyThere);
+        }
+        if self.prices.has_forecast() {
+            return Err(Error::NoMoreTips);
+        }
+        if self.rules.informant > self.cash {
+            return Err(Error::CantAfford);
+        }
+        self.cash -= self.rules.informant;
+        let prices = self
+            .prices
+            .forecast(location, &self.map, &self.catalog, &mut self.rng);
+        self.seen[location.0] = Some(Sighting {
+            day: self.day + 1,
+            prices,
+        });
+        Ok(())
+    }
+
+    fn answer(&mut self, accept: bool, events: &mut Vec<Event>) -> Result<(), Error> {
+        let offer = self.offer.take().ok_or(Error::NoOffer)?;
+        if !accept && offer == Offer::Paraquat {
+            self.unlock(Achievement::JustSayNo, events);
+        }
+        if accept {
+            match offer {
+                Offer::Paraquat => {
+                    events.push(Event::Hallucinated);
+                    self.game_over(End::Paraquat, events);
+                    return Ok(());
+                }
+                Offer::Gun { price, .. } => {
+                    if self.cash >= price {
+                        self.cash -= price;
+                        self.guns += 1;
+                    }
+                }
+                Offer::Coat { space, price } => {
+                    if self.cash >= price {
+                        self.cash -= price;
+                        self.hold += space;
+                    }
+                }
+                Offer::Doctor { cost } => {
+                    if self.cash >= cost {
+                        self.cash -= cost;
+                        self.damage = 0;
+                        self.stats.doctor_bills += cost;
+                    }
+                }
+            }
+        }
+        if self.fight_due {
+            self.roll_fight(events);
+        }
+        Ok(())
+    }
+
+    fn unlock(&mut self, achievement: Achievement, events: &mut Vec<Event>) {
+        if !self.achievements.contains(&achievement) {
+            self.achievements.push(achievement);
+            events.push(Event::Unlocked(achievement));
+        }
+    }
+
+    fn game_over(&mut self, end: End, events: &mut Vec<Event>) {
+        // giving up unlocks nothing
+        if end != End::Forfeit && self.score().score >= 100 {
+            self.unlock(Achievement::TopDog, events);
+        }
+        if end == End::Survived && !self.used_loan_shark {
+            self.unlock(Achievement::LoneWolf, events);
+        }
+        if end == End::Survived && self.hold == 0 {
+            self.unlock(Achievement::Stuffed, events);
+        }
+        self.end = Some(end);
+        self.offer = None;
+        self.fight_due = false;
+        self.cops = 0;
+        events.push(Event::GameOver(end));
+    }
+
+    fn roll_fight(&mut self, events: &mut Vec<Event>) {
+        self.fight_due = false;
+        let fight_chance = self.rng.gen_range(1..=100) * self.here().police / 100 / (self.hold + 1);
+        if fight_chance >= 1 {
+            self.cops = fight_chance / 9 + 2;
+            events.push(Event::CopsChasing { cops: self.cops });
+        }
+    }
+
+    fn run(&mut self, events: &mut Vec<Event>) -> Result<(), Error> {
+        if !self.in_fight() {
+            return Err(Error::NotInFight);
+        }
+        let getaway = self.rng.gen_range(1..=2);
+        if getaway == 1 {
+            events.push(Event::LostThem);
+            self.end_fight();
+        } else {
+            self.cops_fire(events);
+        }
+        Ok(())
+    }
+
+    fn fight(&mut self, events: &mut Vec<Event>) -> Result<(), Error> {
+        if !self.in_fight() {
+            return Err(Error::NotInFight);
+        }
+        if self.guns == 0 {
+            return Err(Error::NoGuns);
+        }
+        events.push(Event::YouFire);
+        let kill_them = self.rng.gen_range(0..=self.guns * 2);
+        if kill_them == 0 {
+            events.push(Event::YouMissed);
+            self.cops_fire(events);
+        } else {
+            self.cops -= 1;
+            self.stats.cops_killed += 1;
+            if self.cops <= 0 {
+                let reward = self.rng.gen_range(200..=1000);
+                self.cash = self.cash.saturating_add(reward);
+                events.push(Event::KilledAll { reward });
+                self.unlock(Achievement::CopKiller, events);
+                self.end_fight();
+            } else {
+                events.push(Event::KilledOne);
+                self.cops_fire(events);
+            }
+        }
+        Ok(())
+    }
+
+    fn cops_fire(&mut self, events: &mut Vec<Event>) {
+        events.push(Event::CopsFire);
+        let damage_hit = self.rng.gen_range(0..=3) * self.cops - self.rng.gen_range(2..=18);
+        if damage_hit <= 0 {
+            events.push(Event::CopsMissed);
+        } else {
+            self.damage += damage_hit;
+            events.push(Event::Hit { damage: damage_hit });
+            if self.damage >= self.rules.max_damage {
+                events.push(Event::Wasted);
+                self.game_over(End::Wasted, events);
+            }
+        }
+    }
+
+    fn end_fight(&mut self) {
+        self.cops = 0;
+        if self.damage > 10 {
+            let doc_price_multiplier = self.rng.gen_range(200..=1000);
+            self.offer = Some(Offer::Doctor {
+                cost: self.damage * doc_price_multiplier / 10,
+            });
+        }
+    }
+
+    // the ones with a chance are rolled on their own, then per_day are
+    // drawn by weight from the rest, never the same one twice a day
+    fn roll_events(&mut self, events: &mut Vec<Event>) {
+        let mut happened = Vec::new();
+        for id in 0..self.events.len() {
+            if let Some(chance) = self.events.spec(id).chance {
+                if self.can_happen(id) && self.rng.gen_range(0..100) < chance {
+                    self.happen(id, events);
+                    happened.push(id);
+                }
+            }
+        }
+
+        for _ in 0..self.events.per_day {
+            let pool: Vec<usize> = (0..self.events.len())
+                .filter(|id| {
+                    let spec = self.events.spec(*id);
+                    spec.chance.is_none()
+                        && spec.weight > 0
+                        && !happened.contains(id)
+                        && self.can_happen(*id)
+                })
+                .collect();
+            if pool.is_empty() {
+                break;
+            }
+            let weights = pool.iter().map(|id| self.events.spec(*id).weight);
+            let weights = WeightedIndex::new(weights).unwrap();
+            let id = pool[self.rng.sample(weights)];
+            self.happen(id, events);
+            happened.push(id);
+        }
+    }
+
+    fn can_happen(&self, id: usize) -> bool {
+        let spec = self.events.spec(id);
+        let day = self.day + 1;
+        let drug = match &spec.drug {
+            Some(name) => match self.catalog.find(name) {
+                Some(drug) => Some(drug),
+                None => return false,
+            },
+            None => None,
+        };
+
+        if let Some(locations) = &spec.locations {
+            let here = self.map.name(self.location);
+            if !locations.iter().any(|name| name.eq_ignore_ascii_case(here)) {
+                return false;
+            }
+        }
+        if let Some(drug) = drug {
+            if spec.changes_price() && !self.sold_here(drug) {
+                return false;
+            }
+            if self.trench_coat(drug) < spec.min_amount {
+                return false;
+            }
+        }
+        if spec.offer.is_some() && self.offer.is_some() {
+            return false;
+        }
+        let within = |value: i64, min: Option<i64>, max: Option<i64>| {
+            min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+        };
+        within(day, spec.min_day, spec.max_day)
+            && within(self.cash, spec.min_cash, spec.max_cash)
+            && within(self.hold, spec.min_space, spec.max_space)
+    }
+
+    fn happen(&mut self, id: usize, events: &mut Vec<Event>) {
+        let spec = self.events.spec(id).clone();
+        *self.stats.events.entry(spec.name.clone()).or_insert(0) += 1;
+        let mut drug = spec
+            .drug
+            .as_deref()
+            .and_then(|name| self.catalog.find(name));
+        let mut n = 0;
+        let cash = self.cash;
+
+        if let Some(drug) = drug {
+            self.prices
+                .scale(self.location, drug, spec.multiply, spec.divide);
+        }
+        if let Some([lo, hi]) = spec.find {
+            n = self.rng.gen_range(lo..=hi).min(self.hold);
+            let found = match drug {
+                Some(drug) => drug,
+                None => Drug(self.rng.gen_range(0..self.catalog.len())),
+            };
+            self.trench_coat[found.0] += n;
+            self.hold -= n;
+            drug = Some(found);
+        }
+        if let Some([lo, hi]) = spec.lose {
+            n = self.rng.gen_range(lo..=hi);
+            for (i, amount) in self.trench_coat.iter_mut().enumerate() {
+                if drug.is_none_or(|drug| drug.0 == i) {
+                    let dropped = *amount / n;
+                    *amount -= dropped;
+                    self.hold += dropped;
+                }
+            }
+        }
+        if let Some(percent) = spec.cash_percent {
+            self.cash = percent_of(self.cash, percent);
+        }
+        if let Some([lo, hi]) = spec.cash {
+            let change = self.rng.gen_range(lo..=hi);
+            self.cash = self.cash.saturating_add(change).max(0);
+            n = change.abs();
+        }
+        if self.cash < cash {
+            self.stats.mugged += 1;
+        }
+        if let Some(kind) = spec.offer {
+            self.offer = Some(self.make_offer(kind, &spec));
+        }
+
+        events.push(Event::Random { id, drug, n });
+    }
+
+    fn make_offer(&mut self, kind: OfferKind, spec: &EventSpec) -> Offer {
+        let roll = |rng: &mut ChaCha12Rng, range: Option<[i64; 2]>| {
+            let [lo, hi] = range.unwrap_or_default();
+            rng.gen_range(lo..=hi)
+        };
+        match kind {
+            OfferKind::Paraquat => Offer::Paraquat,
+            OfferKind::Gun => {
+                let gunstock = ["RUGER", ".38 SPECIAL", "SATURDAY NIGHT SPECIAL", "BARETTA"];
+                let gun = gunstock[self.rng.gen_range(0..gunstock.len())];
+                let price = roll(&mut self.rng, spec.price);
+                Offer::Gun { gun, price }
+            }
+            OfferKind::Coat => {
+                let space = roll(&mut self.rng, spec.space);
+                let price = roll(&mut self.rng, spec.price);
+                Offer::Coat { space, price }
+            }
+        }
+    }
+}
+
+// a day's interest, a debt or a balance that runs away stops at the most
+// an i64 holds instead of wrapping
+fn with_interest(amount: i64, percent: i64) -> i64 {
+    percent_of(amount, percent.saturating_add(100))
+}
+
+// never negative here, rules & events are checked for that
+fn percent_of(amount: i64, percent: i64) -> i64 {
+    let part = i128::from(amount) * i128::from(percent) / 100;
+    i64::try_from(part).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::Market;
+
+    // the classic game with no cops anywhere & only these events, in
+    // the bronx
+    fn with_events(rules: Rules, events: &str) -> Game {
+        let map =
+            include_str!("../data/map.toml").replace("[[location]]", "[[location]]\npolice = 0");
+        let setup = Setup {
+            rules,
+            map: Map::from_toml(&map).unwrap(),
+            events: Events::from_toml(events).unwrap(),
+            ..Setup::default()
+        };
+        Game::with_setup(7, setup)
+    }
+
+    // nothing happening by chance at all
+    fn quiet(rules: Rules) -> Game {
+        with_events(rules, "")
+    }
+
+    fn weed(game: &Game) -> Drug {
+        game.catalog().find("WEED").unwrap()
+    }
+
+    #[test]
+    fn buying_stops_at_cash_and_hold() {
+        let mut game = quiet(Rules::classic());
+        let drug = weed(&game);
+        let most = game.afford(drug);
+        let buy = |amount| Command::Buy { drug, amount };
+        assert_eq!(game.apply(buy(0)), Err(Error::BadAmount));
+        assert_eq!(game.apply(buy(most + 1)), Err(Error::CantAfford));
+
+        let mut game = quiet(Rules {
+            cash: 1_000_000_000,
+            hold: 5,
+            ..Rules::classic()
+        });
+        assert_eq!(game.apply(buy(6)), Err(Error::NoRoom));
+        game.apply(buy(5)).unwrap();
+        assert_eq!(game.trench_coat(drug), 5);
+        assert_eq!(game.hold(), 0);
+    }
+
+    #[test]
+    fn selling_stops_at_what_you_carry() {
+        let mut game = quiet(Rules::classic());
+        let drug = weed(&game);
+        let cash = game.cash();
+        game.apply(Command::Buy { drug, amount: 3 }).unwrap();
+        let sell = |amount| Command::Sell { drug, amount };
+        assert_eq!(game.apply(sell(4)), Err(Error::NotEnough));
+        game.apply(sell(3)).unwrap();
+        assert_eq!(game.cash(), cash);
+        assert_eq!(game.hold(), game.rules().hold);
+    }
+
+    #[test]
+    fn borrowing_stops_at_the_max_loan() {
+        let mut game = quiet(Rules::classic());
+        let room = game.max_loan() - game.debt();
+        assert_eq!(
+            game.apply(Command::Borrow(i64::MAX)),
+            Err(Error::LoanTooBig)
+        );
+        assert_eq!(
+            game.apply(Command::Borrow(room + 1)),
+            Err(Error::LoanTooBig)
+        );
+        game.apply(Command::Borrow(room)).unwrap();
+        assert_eq!(game.debt(), game.max_loan());
+
+        let mut game = quiet(Rules {
+            cash: 1_000_000,
+            ..Rules::classic()
+        });
+        assert_eq!(
+            game.apply(Command::Repay(game.debt() + 1)),
+            Err(Error::MoreThanOwed)
+        );
+        game.apply(Command::Repay(game.debt())).unwrap();
+        assert_eq!(game.debt(), 0);
+    }
+
+    #[test]
+    fn a_jet_adds_a_days_interest() {
+        let mut game = quiet(Rules::classic());
+        game.apply(Command::Deposit(1000)).unwrap();
+        game.apply(Command::Jet(Location(1))).unwrap();
+        assert!(!game.in_fight());
+        assert_eq!(game.debt(), 5500 * 110 / 100);
+        assert_eq!(game.bank(), 1000 * 105 / 100);
+        assert_eq!(game.apply(Command::Deposit(1)), Err(Error::NotHere));
+    }
+
+    // the informant's tip is what the prices there turn out to be
+    #[test]
+    fn a_tip_comes_true() {
+        for (market, limited) in [(Market::Classic, false), (Market::Drifting, true)] {
+            let mut game = quiet(Rules {
+                market,
+                limited,
+                ..Rules::classic()
+            });
+            let (park, manhattan) = (Location(2), Location(3));
+            game.apply(Command::Jet(park)).unwrap();
+            assert_eq!(game.apply(Command::Tip(park)), Err(Error::AlreadyThere));
+            game.apply(Command::Tip(manhattan)).unwrap();
+            assert_eq!(game.apply(Command::Tip(manhattan)), Err(Error::NoMoreTips));
+            let tip = game.seen(manhattan).unwrap().clone();
+
+            game.apply(Command::Jet(manhattan)).unwrap();
+            assert_eq!(tip.day, game.day());
+            assert_eq!(tip.prices, game.prices().row(manhattan), "{market}");
+        }
+    }
+
+    #[test]
+    fn cash_stops_at_the_most_there_is() {
+        let mut game = quiet(Rules::classic());
+        let drug = weed(&game);
+        game.cash = i64::MAX - 1;
+        game.trench_coat[drug.0] = 10;
+        game.apply(Command::Sell { drug, amount: 10 }).unwrap();
+        assert_eq!(game.cash(), i64::MAX);
+
+        for event in ["cash_percent = 200", "cash = [100, 100]"] {
+            let event = format!("[[event]]\nname = \"windfall\"\nchance = 100\n{event}");
+            let mut game = with_events(Rules::classic(), &event);
+            game.cash = i64::MAX - 1;
+            game.start();
+            assert_eq!(game.cash(), i64::MAX, "{event}");
+        }
+    }
+
+    #[test]
+    fn interest_stops_at_the_most_there_is() {
+        assert_eq!(with_interest(i64::MAX, 10), i64::MAX);
+        assert_eq!(with_interest(i64::MAX / 2, 100), i64::MAX - 1);
+        assert_eq!(with_interest(0, 10), 0);
+    }
+
+    // one day, so the next jet ends the game
+    fn last_day(rules: Rules) -> Rules {
+        Rules { days: 1, ..rules }
+    }
+
+    fn unlocked(events: &[Event], achievement: Achievement) -> bool {
+        events.contains(&Event::Unlocked(achievement))
+    }
+
+    #[test]
+    fn paying_it_all_back_is_debt_free() {
+        let mut game = quiet(Rules {
+            cash: 1_000_000,
+            ..Rules::classic()
+        });
+        let events = game.apply(Command::Repay(game.debt() - 1)).unwrap();
+        assert!(!unlocked(&events, Achievement::DebtFree));
+        let events = game.apply(Command::Repay(1)).unwrap();
+        assert!(unlocked(&events, Achievement::DebtFree));
+    }
+
+    #[test]
+    fn turning_down_paraquat_is_just_say_no() {
+        let paraquat = "[[event]]\nname = \"paraquat\"\nchance = 100\noffer = \"paraquat\"";
+        let mut game = with_events(Rules::classic(), paraquat);
+        game.start();
+        assert_eq!(game.offer(), Some(Offer::Paraquat));
+        let events = game.apply(Command::Decline).unwrap();
+        assert!(unlocked(&events, Achievement::JustSayNo));
+
+        let mut game = with_events(Rules::classic(), paraquat);
+        game.start();
+        let events = game.apply(Command::Accept).unwrap();
+        assert!(!unlocked(&events, Achievement::JustSayNo));
+        assert_eq!(game.end(), Some(End::Paraquat));
+    }
+
+    #[test]
+    fn killing_every_cop_is_cop_killer() {
+        let mut game = quiet(Rules::classic());
+        game.guns = 2;
+        game.cops = 2;
+        let mut events = Vec::new();
+        while game.in_fight() {
+            events.extend(game.apply(Command::Fight).unwrap());
+        }
+        assert!(unlocked(&events, Achievement::CopKiller));
+        assert_eq!(game.stats().cops_killed, 2);
+    }
+
+    #[test]
+    fn a_score_of_100_is_top_dog() {
+        let rich = Rules {
+            cash: 60_000_000,
+            ..last_day(Rules::classic())
+        };
+        let mut game = quiet(rich.clone());
+        let events = game.apply(Command::Jet(Location(1))).unwrap();
+        assert!(unlocked(&events, Achievement::TopDog));
+
+        // giving up unlocks nothing
+        let mut game = quiet(rich);
+        let events = game.apply(Command::Forfeit).unwrap();
+        assert!(events
+            .iter()
+            .all(|event| !matches!(event, Event::Unlocked(_))));
+    }
+
+    #[test]
+    fn never_seeing_the_loan_shark_is_lone_wolf() {
+        let mut game = quiet(last_day(Rules::classic()));
+        let events = game.apply(Command::Jet(Location(1))).unwrap();
+        assert!(unlocked(&events, Achievement::LoneWolf));
+
+        for command in [Command::Borrow(100), Command::LoanShark] {
+            let mut game = quiet(last_day(Rules::classic()));
+            game.apply(command).unwrap();
+            let events = game.apply(Command::Jet(Location(1))).unwrap();
+            assert_eq!(game.end(), Some(End::Survived));
+            assert!(!unlocked(&events, Achievement::LoneWolf), "{command:?}");
+        }
+    }
+
+    #[test]
+    fn full_pockets_at_the_end_is_stuffed() {
+        let mut game = quiet(Rules {
+            cash: 100_000,
+            hold: 5,
+            ..last_day(Rules::classic())
+        });
+        let drug = weed(&game);
+        game.apply(Command::Buy { drug, amount: 4 }).unwrap();
+        let mut full = quiet(game.rules().clone());
+        full.apply(Command::Buy { drug, amount: 5 }).unwrap();
+
+        let events = game.apply(Command::Jet(Location(1))).unwrap();
+        assert!(!unlocked(&events, Achievement::Stuffed));
+        let events = full.apply(Command::Jet(Location(1))).unwrap();
+        assert!(unlocked(&events, Achievement::Stuffed));
+    }
+
+    #[test]
+    fn any_event_taking_cash_is_a_mugging() {
+        let pickpocket = "[[event]]\nname = \"pickpocket\"\nchance = 100\ncash = [-100, -100]";
+        let mut game = with_events(Rules::classic(), pickpocket);
+        game.start();
+        assert_eq!(game.stats().mugged, 1);
+
+        let windfall = "[[event]]\nname = \"mugged\"\nchance = 100\ncash = [100, 100]";
+        let mut game = with_events(Rules::classic(), windfall);
+        game.start();
+        assert_eq!(game.stats().mugged, 0);
+    }
+
+    fn random(events: &[Event]) -> Vec<usize> {
+        let ids = events.iter().filter_map(|event| match event {
+            Event::Random { id, .. } => Some(*id),
+            _ => None,
+        });
+        ids.collect()
+    }
+
+    #[test]
+    fn an_event_waits_for_its_day_and_place() {
+        let tour =
+            "[[event]]\nname = \"tour\"\nchance = 100\nmin_day = 2\nlocations = [\"ghetto\"]";
+        let mut game = with_events(Rules::classic(), tour);
+        assert!(random(&game.start()).is_empty());
+        assert!(random(&game.apply(Command::Jet(Location(2))).unwrap()).is_empty());
+        assert_eq!(random(&game.apply(Command::Jet(Location(1))).unwrap()), [0]);
+        assert_eq!(game.stats().events["tour"], 1);
+    }
+
+    #[test]
+    fn the_pool_draws_per_day_never_twice() {
+        let pool = "[[event]]\nname = \"a\"\n[[event]]\nname = \"b\"\nweight = 3";
+        let mut game = with_events(Rules::classic(), &format!("per_day = 3\n{pool}"));
+        let mut drawn = random(&game.start());
+        drawn.sort();
+        assert_eq!(drawn, [0, 1]);
+
+        let mut game = with_events(Rules::classic(), &format!("per_day = 0\n{pool}"));
+        assert!(random(&game.start()).is_empty());
+    }
+}
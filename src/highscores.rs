@@ -0,0 +1,373 @@
+// highscores.rs 🦀
+// the top 10 of every difficulty, kept in highscores.json in the data dir
+
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::dirs;
+use crate::event::End;
+use crate::lock::FileLock;
+use crate::rules::Difficulty;
+
+pub const SCORES_VERSION: u32 = 2;
+
+// where the scores lived before, in whatever directory the game ran from
+pub static LEGACY_PATH: &str = "highscores.txt";
+
+// version 1 scores & the old text file only had money, rank, date &
+// difficulty, the rest default to empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    #[serde(default)]
+    pub name: String,
+    pub total_money: i64,
+    pub dealer_rank: String,
+    pub date: String,
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub days: i64,
+    #[serde(default)]
+    pub end: Option<End>,
+    #[serde(default)]
+    pub peak: i64,
+    #[serde(default)]
+    pub time: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Money,
+    Peak,
+    Days,
+    Newest,
+}
+
+impl SortBy {
+    pub const ALL: [SortBy; 4] = [SortBy::Money, SortBy::Peak, SortBy::Days, SortBy::Newest];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortBy::Money => "MONEY",
+            SortBy::Peak => "PEAK",
+            SortBy::Days => "DAYS",
+            SortBy::Newest => "NEWEST",
+        }
+    }
+
+    pub fn next(&self) -> SortBy {
+        let i = SortBy::ALL.iter().position(|sort| sort == self).unwrap();
+        SortBy::ALL[(i + 1) % SortBy::ALL.len()]
+    }
+}
+
+impl PartialEq for HighScore {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_money == other.total_money
+    }
+}
+
+impl Eq for HighScore {}
+
+impl PartialOrd for HighScore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HighScore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.total_money.cmp(&self.total_money)
+    }
+}
+
+// read on its own first, a newer game's file might not parse as ours
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScoreFile {
+    version: u32,
+    scores: Vec<HighScore>,
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("highscores.json"))
+}
+
+pub fn load(path: &Path) -> io::Result<Vec<HighScore>> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let Version { version } = serde_json::from_str(&json)?;
+    if version > SCORES_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("unsupported high score version {version}"),
+        ));
+    }
+    let file: ScoreFile = serde_json::from_str(&json)?;
+    Ok(file.scores)
+}
+
+// adds a score under the lock, so two games ending at once both get in,
+// & hands back the table it was written to
+pub fn record(path: &Path, score: HighScore) -> io::Result<Vec<HighScore>> {
+    record_with(path, Path::new(LEGACY_PATH), score)
+}
+
+fn record_with(path: &Path, legacy: &Path, score: HighScore) -> io::Result<Vec<HighScore>> {
+    let _lock = FileLock::acquire(&path.with_extension("lock"))?;
+
+    // a file that won't parse is kept as highscores.json.bad & the table
+    // starts over, a newer game's file is left alone
+    let mut broken = false;
+    let mut scores = match load(path) {
+        Ok(scores) => scores,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+            ) =>
+        {
+            fs::rename(path, path.with_extension("json.bad"))?;
+            broken = true;
+            Vec::new()
+        }
+        Err(e) => return Err(e),
+    };
+    // only ever into a new table, a broken one had the old scores already
+    let migrating = !broken && !path.exists() && legacy.exists();
+    if migrating {
+        scores = load_legacy(legacy)?;
+    }
+
+    scores.push(score);
+    let scores = top_ten(scores);
+
+    let json = serde_json::to_string_pretty(&ScoreFile {
+        version: SCORES_VERSION,
+        scores,
+    })?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)?;
+
+    // moved out of the way so it isn't picked up again
+    if migrating {
+        let _ = fs::rename(legacy, legacy.with_extension("txt.old"));
+    }
+    load(path)
+}
+
+// the old comma separated lines, the difficulty was added late
+pub fn load_legacy(path: &Path) -> io::Result<Vec<HighScore>> {
+    let reader = BufReader::new(fs::File::open(path)?);
+    let mut scores = Vec::new();
+    for line in reader.lines().map_while(Result::ok) {
+        let parts: Vec<&str> = line.trim().split(',').collect();
+        if parts.len() == 3 || parts.len() == 4 {
+            let total_money = parts[0].parse::<i64>();
+            let difficulty = parts.get(3).map_or(Ok(Difficulty::Classic), |d| d.parse());
+            if let (Ok(total_money), Ok(difficulty)) = (total_money, difficulty) {
+                scores.push(HighScore {
+                    name: String::new(),
+                    total_money,
+                    dealer_rank: parts[1].to_string(),
+                    date: parts[2].to_string(),
+                    difficulty,
+                    seed: None,
+                    days: 0,
+                    end: None,
+                    peak: 0,
+                    time: 0,
+                });
+            }
+        }
+    }
+    Ok(scores)
+}
+
+// a top 10 for every difficulty, they're never ranked together
+pub fn top_ten(mut scores: Vec<HighScore>) -> Vec<HighScore> {
+    scores.sort();
+    let mut kept = HashMap::new();
+    scores.retain(|score| {
+        let count = kept.entry(score.difficulty).or_insert(0);
+        *count += 1;
+        *count <= 10
+    });
+    scores
+}
+
+// the leaderboard, one difficulty or all of them, best first
+pub fn sorted(scores: &[HighScore], only: Option<Difficulty>, sort: SortBy) -> Vec<HighScore> {
+    let mut scores: Vec<HighScore> = scores
+        .iter()
+        .filter(|score| only.is_none_or(|difficulty| score.difficulty == difficulty))
+        .cloned()
+        .collect();
+    match sort {
+        SortBy::Money => scores.sort(),
+        SortBy::Peak => scores.sort_by_key(|score| Reverse(score.peak)),
+        SortBy::Days => scores.sort_by_key(|score| Reverse(score.days)),
+        SortBy::Newest => scores.sort_by_key(|score| Reverse(score.time)),
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(total_money: i64) -> HighScore {
+        HighScore {
+            name: "TEST".to_string(),
+            total_money,
+            dealer_rank: "KINGPIN".to_string(),
+            date: "12 / 04 / 83".to_string(),
+            difficulty: Difficulty::Classic,
+            seed: Some(7),
+            days: 31,
+            end: Some(End::Survived),
+            peak: total_money,
+            time: 0,
+        }
+    }
+
+    fn scored(name: &str, total_money: i64, difficulty: Difficulty) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            difficulty,
+            ..score(total_money)
+        }
+    }
+
+    fn names(scores: &[HighScore]) -> Vec<&str> {
+        scores.iter().map(|score| score.name.as_str()).collect()
+    }
+
+    #[test]
+    fn every_difficulty_keeps_its_own_ten() {
+        let mut scores: Vec<HighScore> = (1..=12)
+            .map(|money| scored("CLASSIC", money, Difficulty::Classic))
+            .collect();
+        scores.extend((1..=3).map(|money| scored("HARD", money * 100, Difficulty::Hard)));
+
+        let kept = top_ten(scores);
+        let classic: Vec<i64> = kept
+            .iter()
+            .filter(|score| score.difficulty == Difficulty::Classic)
+            .map(|score| score.total_money)
+            .collect();
+        assert_eq!(classic, (3..=12).rev().collect::<Vec<_>>());
+        assert_eq!(
+            kept.iter()
+                .filter(|score| score.difficulty == Difficulty::Hard)
+                .count(),
+            3
+        );
+        // best first across the lot
+        assert!(kept
+            .windows(2)
+            .all(|pair| pair[0].total_money >= pair[1].total_money));
+    }
+
+    #[test]
+    fn the_leaderboard_sorts_every_way() {
+        // money, peak, days & time each put them in a different order
+        let mut rich = scored("RICH", 900, Difficulty::Classic);
+        (rich.peak, rich.days, rich.time) = (900, 10, 2);
+        let mut peaked = scored("PEAKED", 500, Difficulty::Classic);
+        (peaked.peak, peaked.days, peaked.time) = (5000, 20, 1);
+        let mut lasted = scored("LASTED", 100, Difficulty::Hard);
+        (lasted.peak, lasted.days, lasted.time) = (100, 31, 0);
+        let mut newest = scored("NEWEST", 50, Difficulty::Easy);
+        (newest.peak, newest.days, newest.time) = (50, 5, 3);
+        let scores = [lasted, newest, rich, peaked];
+
+        let by = |sort| sorted(&scores, None, sort);
+        assert_eq!(
+            names(&by(SortBy::Money)),
+            ["RICH", "PEAKED", "LASTED", "NEWEST"]
+        );
+        assert_eq!(
+            names(&by(SortBy::Peak)),
+            ["PEAKED", "RICH", "LASTED", "NEWEST"]
+        );
+        assert_eq!(
+            names(&by(SortBy::Days)),
+            ["LASTED", "PEAKED", "RICH", "NEWEST"]
+        );
+        assert_eq!(
+            names(&by(SortBy::Newest)),
+            ["NEWEST", "RICH", "PEAKED", "LASTED"]
+        );
+
+        let classic = sorted(&scores, Some(Difficulty::Classic), SortBy::Days);
+        assert_eq!(names(&classic), ["PEAKED", "RICH"]);
+        assert!(sorted(&scores, Some(Difficulty::Custom), SortBy::Money).is_empty());
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("drugwars-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn a_broken_file_is_kept_aside() {
+        let dir = scratch("broken-scores");
+        let path = dir.join("highscores.json");
+        fs::write(&path, "{ \"version\": 2, \"scores\": [").unwrap();
+
+        let scores = record(&path, score(1_000_000)).unwrap();
+        assert_eq!(scores.len(), 1);
+        assert!(dir.join("highscores.json.bad").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn old_scores_come_over_once() {
+        let dir = scratch("legacy-scores");
+        let path = dir.join("highscores.json");
+        let legacy = dir.join("highscores.txt");
+        fs::write(&legacy, "2000000,OWN THE BLOCK,12 / 04 / 83\n").unwrap();
+
+        let scores = record_with(&path, &legacy, score(1_000_000)).unwrap();
+        assert_eq!(scores.len(), 2);
+        assert!(!legacy.exists());
+        assert!(dir.join("highscores.txt.old").exists());
+
+        // a table that breaks later starts over without them
+        fs::write(&legacy, "2000000,OWN THE BLOCK,12 / 04 / 83\n").unwrap();
+        fs::write(&path, "{ \"version\": 2, \"scores\": [").unwrap();
+        let scores = record_with(&path, &legacy, score(1_000_000)).unwrap();
+        assert_eq!(scores.len(), 1);
+        assert!(legacy.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_newer_file_is_left_alone() {
+        let dir = scratch("newer-scores");
+        let path = dir.join("highscores.json");
+        let newer = format!("{{ \"version\": {}, \"table\": {{}} }}", SCORES_VERSION + 1);
+        fs::write(&path, &newer).unwrap();
+
+        assert!(record(&path, score(1_000_000)).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        assert!(!dir.join("highscores.json.bad").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
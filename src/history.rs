@@ -0,0 +1,163 @@
+// history.rs 🦀
+// every price the player has seen, by drug, place & day
+
+use serde::{Deserialize, Serialize};
+
+use crate::drug::Drug;
+use crate::location::Location;
+use crate::market::Sighting;
+
+// the cheapest, the dearest & the average a drug's been seen at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spread {
+    pub low: i64,
+    pub high: i64,
+    pub average: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    // oldest first, one for every day & place, the last prices of that day
+    seen: Vec<(Location, Sighting)>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // a deal or an event the same day in the same place only updates it
+    pub fn record(&mut self, location: Location, sighting: Sighting) {
+        match self.seen.last_mut() {
+            Some((last, seen)) if *last == location && seen.day == sighting.day => {
+                *seen = sighting;
+            }
+            _ => self.seen.push((location, sighting)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    pub(crate) fn fits(&self, locations: usize, drugs: usize) -> bool {
+        self.seen
+            .iter()
+            .all(|(location, seen)| location.0 < locations && seen.fits(drugs))
+    }
+
+    // the day, the place & the price, wherever the drug was sold
+    pub fn prices(&self, drug: Drug) -> impl Iterator<Item = (i64, Location, i64)> + '_ {
+        self.seen
+            .iter()
+            .map(move |(location, seen)| (seen.day, *location, seen.price(drug)))
+            .filter(|(_, _, price)| *price > 0)
+    }
+
+    pub fn spread(&self, drug: Drug) -> Option<Spread> {
+        let prices: Vec<i64> = self.prices(drug).map(|(_, _, price)| price).collect();
+        let low = *prices.iter().min()?;
+        let high = *prices.iter().max()?;
+        let total: i64 = prices.iter().sum();
+        Some(Spread {
+            low,
+            high,
+            average: (total as f64 / prices.len() as f64).round() as i64,
+        })
+    }
+
+    // where you were every day from the first to the last
+    pub fn places(&self, last: i64) -> Vec<Option<Location>> {
+        Self::by_day(
+            last,
+            self.seen
+                .iter()
+                .map(|(location, seen)| (seen.day, *location)),
+        )
+    }
+
+    // a drug's price every day from the first to the last, none on a day
+    // it wasn't sold where you were
+    pub fn daily(&self, drug: Drug, last: i64) -> Vec<Option<i64>> {
+        Self::by_day(last, self.prices(drug).map(|(day, _, price)| (day, price)))
+    }
+
+    fn by_day<T>(last: i64, seen: impl Iterator<Item = (i64, T)>) -> Vec<Option<T>> {
+        let mut days: Vec<Option<T>> = (0..=last).map(|_| None).collect();
+        for (day, value) in seen {
+            if let Some(slot) = usize::try_from(day).ok().and_then(|day| days.get_mut(day)) {
+                *slot = Some(value);
+            }
+        }
+        days
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seen(day: i64, prices: [i64; 2]) -> Sighting {
+        Sighting {
+            day,
+            prices: prices.to_vec(),
+        }
+    }
+
+    // the bronx on day 0 twice, the ghetto on day 1 & the bronx again on
+    // day 3, cocaine everywhere & heroin only in the ghetto
+    fn history() -> History {
+        let (bronx, ghetto) = (Location(0), Location(1));
+        let mut history = History::new();
+        history.record(bronx, seen(0, [10, 0]));
+        history.record(bronx, seen(0, [12, 0]));
+        history.record(ghetto, seen(1, [20, 5]));
+        history.record(bronx, seen(3, [30, 0]));
+        history
+    }
+
+    #[test]
+    fn a_day_in_a_place_is_seen_once() {
+        let prices: Vec<_> = history().prices(Drug(0)).collect();
+        assert_eq!(
+            prices,
+            [
+                (0, Location(0), 12),
+                (1, Location(1), 20),
+                (3, Location(0), 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn the_spread_leaves_out_where_it_isnt_sold() {
+        let history = history();
+        let cocaine = Spread {
+            low: 12,
+            high: 30,
+            average: 21,
+        };
+        assert_eq!(history.spread(Drug(0)), Some(cocaine));
+        let heroin = Spread {
+            low: 5,
+            high: 5,
+            average: 5,
+        };
+        assert_eq!(history.spread(Drug(1)), Some(heroin));
+        assert_eq!(History::new().spread(Drug(0)), None);
+    }
+
+    #[test]
+    fn every_day_gets_a_slot() {
+        let history = history();
+        assert_eq!(
+            history.daily(Drug(0), 4),
+            [Some(12), Some(20), None, Some(30), None]
+        );
+        assert_eq!(history.daily(Drug(1), 3), [None, Some(5), None, None]);
+        // days past the last are left off
+        assert_eq!(history.daily(Drug(0), 1), [Some(12), Some(20)]);
+        let (bronx, ghetto) = (Some(Location(0)), Some(Location(1)));
+        assert_eq!(history.places(3), [bronx, ghetto, None, bronx]);
+    }
+}
@@ -0,0 +1,172 @@
+// journal.rs 🦀
+// every key, number & name the player typed, so a game can be played back
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::dirs;
+use crate::setup::Setup;
+
+pub const JOURNAL_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Key(char),
+    Number(i64),
+    Text(String),
+}
+
+pub struct JournalWriter {
+    file: File,
+}
+
+impl JournalWriter {
+    // the seed & the whole setup, rules, drugs, map & events, so a replay
+    // doesn't need the files the game was played with
+    pub fn create(path: &Path, seed: u64, setup: &Setup) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = File::create(path)?;
+        writeln!(file, "drugwars-journal {JOURNAL_VERSION}")?;
+        writeln!(file, "seed {seed}")?;
+        writeln!(file, "setup {}", serde_json::to_string(setup)?)?;
+        Ok(JournalWriter { file })
+    }
+
+    // written line by line so a crash still leaves a usable journal
+    pub fn record(&mut self, input: Input) -> io::Result<()> {
+        match input {
+            Input::Key(c) => writeln!(self.file, "key {}", c as u32)?,
+            Input::Number(n) => writeln!(self.file, "num {n}")?,
+            Input::Text(text) => writeln!(self.file, "text {text}")?,
+        }
+        self.file.flush()
+    }
+}
+
+pub struct Journal {
+    pub seed: u64,
+    pub setup: Setup,
+    pub inputs: VecDeque<Input>,
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("journal.txt"))
+}
+
+fn bad_line(n: usize, line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("bad journal line {}: {:?}", n + 1, line),
+    )
+}
+
+pub fn load(path: &Path) -> io::Result<Journal> {
+    let text = fs::read_to_string(path)?;
+    let mut lines = text.lines().enumerate();
+
+    match lines.next() {
+        Some((_, line)) if line == format!("drugwars-journal {JOURNAL_VERSION}") => {}
+        Some((_, line)) if line.starts_with("drugwars-journal ") => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{line} is from an older game, only version {JOURNAL_VERSION} replays"),
+            ));
+        }
+        Some((n, line)) => return Err(bad_line(n, line)),
+        None => return Err(bad_line(0, "")),
+    }
+
+    let seed = match lines.next() {
+        Some((n, line)) => line
+            .strip_prefix("seed ")
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| bad_line(n, line))?,
+        None => return Err(bad_line(1, "")),
+    };
+
+    let setup: Setup = match lines.next() {
+        Some((_, line)) if line.starts_with("setup ") => {
+            serde_json::from_str(&line["setup ".len()..]).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad journal setup: {e}"),
+                )
+            })?
+        }
+        Some((n, line)) => return Err(bad_line(n, line)),
+        None => return Err(bad_line(2, "")),
+    };
+    setup.validate()?;
+
+    let mut inputs = VecDeque::new();
+    for (n, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let input = match line.split_once(' ') {
+            Some(("key", code)) => code.parse().ok().and_then(char::from_u32).map(Input::Key),
+            Some(("num", num)) => num.parse().ok().map(Input::Number),
+            Some(("text", text)) => Some(Input::Text(text.to_string())),
+            _ => None,
+        };
+        inputs.push_back(input.ok_or_else(|| bad_line(n, line))?);
+    }
+
+    Ok(Journal {
+        seed,
+        setup,
+        inputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("drugwars-{name}-{}.txt", std::process::id()))
+    }
+
+    #[test]
+    fn a_journal_reads_back_what_was_written() {
+        let path = scratch("journal");
+        let inputs = [
+            Input::Key('n'),
+            Input::Key('ñ'),
+            Input::Number(1_500),
+            Input::Text("EL CAPO 2".to_string()),
+            Input::Text(String::new()),
+        ];
+        let mut journal = JournalWriter::create(&path, 7, &Setup::default()).unwrap();
+        for input in inputs.clone() {
+            journal.record(input).unwrap();
+        }
+
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.seed, 7);
+        assert_eq!(loaded.setup.rules, Setup::default().rules);
+        assert_eq!(loaded.inputs, inputs);
+    }
+
+    #[test]
+    fn a_bad_journal_says_where() {
+        let path = scratch("bad-journal");
+        JournalWriter::create(&path, 7, &Setup::default()).unwrap();
+        let good = fs::read_to_string(&path).unwrap();
+
+        let older = good.replace(&format!("journal {JOURNAL_VERSION}"), "journal 1");
+        fs::write(&path, older).unwrap();
+        let e = load(&path).err().unwrap();
+        assert!(e.to_string().contains("older game"), "{e}");
+
+        fs::write(&path, format!("{good}key 110\nkey\n")).unwrap();
+        let e = load(&path).err().unwrap();
+        assert!(e.to_string().contains("bad journal line 5"), "{e}");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,45 @@
+// lib.rs 🦀
+// 40th Anniversary Drugwars in Rust
+// urrick hunt
+
+#![allow(clippy::uninlined_format_args)]
+#![allow(clippy::trivially_copy_pass_by_ref)]
+#![allow(clippy::explicit_iter_loop)]
+#![allow(clippy::unused_self)]
+
+pub mod achievement;
+pub mod catalog;
+pub mod dirs;
+pub mod drug;
+pub mod event;
+pub mod events;
+pub mod game;
+pub mod highscores;
+pub mod history;
+pub mod journal;
+pub mod locale;
+pub mod location;
+mod lock;
+pub mod map;
+pub mod market;
+pub mod profile;
+pub mod rules;
+pub mod save;
+pub mod score;
+pub mod setup;
+pub mod stats;
+
+pub use achievement::Achievement;
+pub use catalog::Catalog;
+pub use drug::Drug;
+pub use event::{End, Event, Offer};
+pub use events::Events;
+pub use game::{Command, Error, Game};
+pub use history::{History, Spread};
+pub use locale::Locale;
+pub use location::Location;
+pub use map::Map;
+pub use market::{Market, Prices, Sighting, Stock};
+pub use rules::{Difficulty, Rules};
+pub use score::{Rank, Score};
+pub use setup::Setup;
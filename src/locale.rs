@@ -0,0 +1,290 @@
+// locale.rs 🦀
+// the game's words, hotkeys & numbers in other languages, from data/locales
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+static ENGLISH: &str = include_str!("../data/locales/en.toml");
+static BUILT_IN: [(&str, &str); 2] = [
+    ("en", ENGLISH),
+    ("es", include_str!("../data/locales/es.toml")),
+];
+
+pub const NAMES: &str = "en, es";
+
+// hotkeys answering the same prompt, none of them can share a letter
+const PROMPTS: [&[&str]; 9] = [
+    &["yes", "no", "scores", "profiles"],
+    &[
+        "buy",
+        "sell",
+        "jet",
+        "save",
+        "loan_shark",
+        "bank",
+        "stash",
+        "informant",
+        "history",
+        "quit",
+    ],
+    &["run", "fight"],
+    &["difficulty", "sort", "back"],
+    &["next", "previous", "play_as", "back"],
+    &["resume", "save_quit", "forfeit", "help"],
+    &["repay", "borrow"],
+    &["deposit", "withdraw"],
+    &["deposit", "take"],
+];
+
+// pause is heard at every prompt of a game, so it can't be one of their keys
+const IN_GAME: [&str; 8] = ["yes", "no", "buy", "sell", "jet", "save", "run", "fight"];
+
+#[derive(Debug, Clone)]
+pub struct Locale {
+    pub language: String,
+    // between every three digits
+    pub thousands: String,
+    // between the whole & the fraction
+    pub decimal: String,
+    keys: HashMap<String, char>,
+    text: HashMap<String, String>,
+    // the messages of the random events, by their name in events.toml
+    events: HashMap<String, Vec<String>>,
+    // drugs, places, ranks & the rest the game calls by an english name
+    names: HashMap<String, String>,
+    // a drug's key by its english name, where the name here needs another
+    drug_keys: HashMap<String, char>,
+}
+
+// a locale file, where everything's optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LocaleFile {
+    language: Option<String>,
+    thousands: Option<String>,
+    decimal: Option<String>,
+    keys: HashMap<String, char>,
+    text: HashMap<String, String>,
+    events: HashMap<String, Vec<String>>,
+    names: HashMap<String, String>,
+    drug_keys: HashMap<String, char>,
+}
+
+impl Locale {
+    pub fn english() -> Self {
+        let file = Self::parse(ENGLISH).unwrap();
+        Locale {
+            language: file.language.unwrap_or_default(),
+            thousands: file.thousands.unwrap_or_default(),
+            decimal: file.decimal.unwrap_or_default(),
+            keys: file.keys,
+            text: file.text,
+            events: file.events,
+            names: file.names,
+            drug_keys: file.drug_keys,
+        }
+    }
+
+    // es, es_ES or es_ES.UTF-8
+    pub fn built_in(lang: &str) -> Option<Self> {
+        let lang = lang
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        BUILT_IN
+            .iter()
+            .find(|(name, _)| *name == lang)
+            .map(|(_, text)| Self::from_toml(text).unwrap())
+    }
+
+    // the first of LC_ALL, LC_MESSAGES & LANG that's set decides, english
+    // when it's one we don't have
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|lang| Self::built_in(&lang))
+            .unwrap_or_else(Self::english)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    // anything the file leaves out comes from the english
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let mut locale = Self::english();
+        let other = Self::parse(text)?;
+        if let Some(language) = other.language {
+            locale.language = language;
+        }
+        if let Some(thousands) = other.thousands {
+            locale.thousands = thousands;
+        }
+        if let Some(decimal) = other.decimal {
+            locale.decimal = decimal;
+        }
+        locale.keys.extend(other.keys);
+        locale.text.extend(other.text);
+        locale.events.extend(other.events);
+        locale.names.extend(other.names);
+        locale.drug_keys.extend(other.drug_keys);
+        locale.validate()?;
+        Ok(locale)
+    }
+
+    fn parse(text: &str) -> io::Result<LocaleFile> {
+        toml::from_str(text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        let bad = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+
+        for (name, key) in &self.keys {
+            if !key.is_ascii_alphanumeric() {
+                return bad(format!("the {name} key has to be a plain letter or digit"));
+            }
+        }
+        for (name, key) in &self.drug_keys {
+            if !key.is_ascii_alphabetic() {
+                return bad(format!("the {name} key has to be a letter"));
+            }
+        }
+        for prompt in PROMPTS {
+            let mut seen = HashMap::new();
+            for name in prompt {
+                let key = self.key(name);
+                if let Some(other) = seen.insert(key, name) {
+                    return bad(format!("the {other} & {name} keys are both {key}"));
+                }
+            }
+        }
+        let pause = self.key("pause");
+        if let Some(name) = IN_GAME.iter().find(|name| self.key(name) == pause) {
+            return bad(format!("the pause & {name} keys are both {pause}"));
+        }
+        if self.thousands.chars().any(|c| c.is_ascii_digit()) {
+            return bad("numbers can't be grouped with digits".to_string());
+        }
+        if self.decimal.is_empty() || self.decimal.chars().any(|c| c.is_ascii_digit()) {
+            return bad("the decimal point has to be something other than a digit".to_string());
+        }
+        if self.decimal == self.thousands {
+            return bad("the decimal point & the thousands can't be the same".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.text.get(key).map_or(key, String::as_str)
+    }
+
+    // a message with its {name}s filled in
+    pub fn fill(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.text(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+
+    // lowercase, the way keys are compared
+    pub fn key(&self, name: &str) -> char {
+        self.keys
+            .get(name)
+            .map_or('?', |key| key.to_ascii_lowercase())
+    }
+
+    // a drug's key in this language, by the drug's english name
+    pub fn drug_key(&self, english: &str) -> Option<char> {
+        self.drug_keys.get(english).map(char::to_ascii_lowercase)
+    }
+
+    pub fn event(&self, name: &str) -> Option<&[String]> {
+        self.events.get(name).map(Vec::as_slice)
+    }
+
+    pub fn name<'a>(&'a self, english: &'a str) -> &'a str {
+        self.names.get(english).map_or(english, String::as_str)
+    }
+
+    pub fn number(&self, n: i64) -> String {
+        let digits = n.unsigned_abs().to_string();
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i != 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(&self.thousands);
+            }
+            grouped.push(c);
+        }
+        if n < 0 {
+            format!("-{grouped}")
+        } else {
+            grouped
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // english, with a line of it swapped for another
+    fn english(from: &str, to: &str) -> io::Result<Locale> {
+        assert!(ENGLISH.contains(from), "{from}");
+        Locale::from_toml(&ENGLISH.replace(from, to))
+    }
+
+    #[test]
+    fn a_language_goes_by_its_first_part() {
+        for lang in ["es", "es_ES", "ES-mx", "es_ES.UTF-8"] {
+            assert_eq!(
+                Locale::built_in(lang).unwrap().language,
+                "español",
+                "{lang}"
+            );
+        }
+        assert!(Locale::built_in("fr_FR.UTF-8").is_none());
+        assert!(Locale::built_in("").is_none());
+    }
+
+    #[test]
+    fn what_a_language_leaves_out_is_english() {
+        let spanish = Locale::built_in("es").unwrap();
+        assert_eq!(spanish.name("WEED"), "MARÍA");
+        assert_eq!(spanish.drug_key("WEED"), Some('m'));
+        assert_eq!(spanish.drug_key("COCAINE"), None);
+        assert_eq!(spanish.number(-1234567), "-1.234.567");
+
+        let bare = Locale::from_toml("language = \"bare\"").unwrap();
+        let english = Locale::english();
+        assert_eq!(bare.key("buy"), english.key("buy"));
+        assert_eq!(bare.text("cant_load"), english.text("cant_load"));
+        assert_eq!(bare.name("GLUE"), "GLUE");
+        assert_eq!(bare.number(1000), "1,000");
+        assert_eq!(
+            bare.fill("need", &[("width", &80), ("height", &24)]),
+            "NEED 80x24"
+        );
+    }
+
+    #[test]
+    fn a_bad_language_is_turned_away() {
+        assert!(english("language = \"english\"", "language = \"english\"").is_ok());
+        assert!(english("pause = \"q\"", "pause = \"?\"").is_err());
+        assert!(english("pause = \"q\"", "pause = \"b\"").is_err());
+        assert!(english("sell = \"s\"", "sell = \"b\"").is_err());
+        assert!(english("thousands = \",\"", "thousands = \"0\"").is_err());
+        assert!(english("decimal = \".\"", "decimal = \"\"").is_err());
+        assert!(english("decimal = \".\"", "decimal = \",\"").is_err());
+        assert!(Locale::from_toml("[drug_keys]\nWEED = \"4\"").is_err());
+        assert!(Locale::from_toml("colour = \"red\"").is_err());
+    }
+}
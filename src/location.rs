@@ -0,0 +1,13 @@
+// location.rs 🦀
+
+use serde::{Deserialize, Serialize};
+
+// a place on the game's map, the index of its [[location]] entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Location(pub(crate) usize);
+
+impl Location {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
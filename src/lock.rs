@@ -0,0 +1,85 @@
+// lock.rs 🦀
+// an exclusive lock on a file next to the data, released when dropped
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    // blocks until no other game holds the lock
+    pub fn acquire(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        lock(&file)?;
+        Ok(FileLock { file })
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        unlock(&self.file);
+    }
+}
+
+#[cfg(unix)]
+fn lock(file: &File) -> io::Result<()> {
+    use nix::fcntl::{flock, FlockArg};
+    use std::os::unix::io::AsRawFd;
+
+    flock(file.as_raw_fd(), FlockArg::LockExclusive).map_err(io::Error::from)
+}
+
+#[cfg(unix)]
+fn unlock(file: &File) {
+    use nix::fcntl::{flock, FlockArg};
+    use std::os::unix::io::AsRawFd;
+
+    let _ = flock(file.as_raw_fd(), FlockArg::Unlock);
+}
+
+#[cfg(windows)]
+fn lock(file: &File) -> io::Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::fileapi::LockFileEx;
+    use winapi::um::minwinbase::{LOCKFILE_EXCLUSIVE_LOCK, OVERLAPPED};
+
+    unsafe {
+        let mut overlapped: OVERLAPPED = std::mem::zeroed();
+        let handle = file.as_raw_handle() as _;
+        if LockFileEx(handle, LOCKFILE_EXCLUSIVE_LOCK, 0, !0, !0, &mut overlapped) == 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn unlock(file: &File) {
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::fileapi::UnlockFileEx;
+    use winapi::um::minwinbase::OVERLAPPED;
+
+    unsafe {
+        let mut overlapped: OVERLAPPED = std::mem::zeroed();
+        let handle = file.as_raw_handle() as _;
+        UnlockFileEx(handle, 0, !0, !0, &mut overlapped);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lock(_file: &File) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn unlock(_file: &File) {}
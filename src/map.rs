@@ -0,0 +1,217 @@
+// map.rs 🦀
+// where you can jet to, the classic boroughs live in data/map.toml
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::catalog::Catalog;
+use crate::drug::Drug;
+use crate::location::Location;
+
+static CLASSIC: &str = include_str!("../data/map.toml");
+
+fn hundred() -> i64 {
+    100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationSpec {
+    pub name: String,
+    #[serde(default)]
+    pub drugs: Option<Vec<String>>,
+    #[serde(default)]
+    pub prices: BTreeMap<String, f64>,
+    #[serde(default = "hundred")]
+    pub police: i64,
+    #[serde(default)]
+    pub loan_shark: bool,
+    #[serde(default)]
+    pub bank: bool,
+    #[serde(default)]
+    pub stash: bool,
+    #[serde(default)]
+    pub informant: bool,
+}
+
+impl LocationSpec {
+    // the loan shark, the bank, the stash or the informant
+    pub fn has_services(&self) -> bool {
+        self.loan_shark || self.bank || self.stash || self.informant
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Map {
+    pub name: String,
+    #[serde(rename = "location")]
+    locations: Vec<LocationSpec>,
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Map {
+    pub fn classic() -> Self {
+        Self::from_toml(CLASSIC).unwrap()
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let map: Map = toml::from_str(text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        map.validate()?;
+        Ok(map)
+    }
+
+    pub(crate) fn validate(&self) -> io::Result<()> {
+        if self.locations.is_empty() || self.locations.len() > 9 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "a map needs 1 to 9 locations",
+            ));
+        }
+        Ok(())
+    }
+
+    // drug names have to match the catalog the map is played with
+    pub fn check(&self, catalog: &Catalog) -> io::Result<()> {
+        let bad = |msg: String| Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+
+        for spec in &self.locations {
+            // each drug once in a list, whatever its case, or which one counts is a guess
+            let lists: [Vec<&String>; 2] = [
+                spec.drugs.iter().flatten().collect(),
+                spec.prices.keys().collect(),
+            ];
+            for names in lists {
+                let mut seen = HashSet::new();
+                for name in names {
+                    let Some(drug) = catalog.find(name) else {
+                        return bad(format!("{}: no drug called {}", spec.name, name));
+                    };
+                    if !seen.insert(drug) {
+                        return bad(format!("{}: {} is listed twice", spec.name, name));
+                    }
+                }
+            }
+            if spec.prices.values().any(|m| *m <= 0.0) {
+                return bad(format!("{}: price multipliers must be positive", spec.name));
+            }
+            if spec.police < 0 {
+                return bad(format!("{}: police can't be negative", spec.name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    pub fn start(&self) -> Location {
+        Location(0)
+    }
+
+    pub fn locations(&self) -> impl Iterator<Item = Location> {
+        (0..self.locations.len()).map(Location)
+    }
+
+    pub fn spec(&self, location: Location) -> &LocationSpec {
+        &self.locations[location.0]
+    }
+
+    pub fn name(&self, location: Location) -> &str {
+        &self.locations[location.0].name
+    }
+
+    pub fn from_key(&self, c: char) -> Option<Location> {
+        let n = c.to_digit(10)? as usize;
+        (1..=self.locations.len())
+            .contains(&n)
+            .then(|| Location(n - 1))
+    }
+
+    pub fn sells(&self, location: Location, catalog: &Catalog, drug: Drug) -> bool {
+        match &self.spec(location).drugs {
+            Some(drugs) => drugs.iter().any(|name| catalog.find(name) == Some(drug)),
+            None => true,
+        }
+    }
+
+    pub fn price_multiplier(&self, location: Location, catalog: &Catalog, drug: Drug) -> f64 {
+        self.spec(location)
+            .prices
+            .iter()
+            .find(|(name, _)| catalog.find(name) == Some(drug))
+            .map_or(1.0, |(_, m)| *m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bronx(line: &str) -> io::Result<()> {
+        Map::from_toml(&format!(
+            "name = \"TEST\"\n[[location]]\nname = \"BRONX\"\n{line}\n"
+        ))?
+        .check(&Catalog::classic())
+    }
+
+    #[test]
+    fn a_drug_is_listed_once() {
+        assert!(bronx("prices = { COCAINE = 1.5 }").is_ok());
+        assert!(bronx("prices = { COCAINE = 1.5, cocaine = 0.5 }").is_err());
+        assert!(bronx("drugs = [\"WEED\", \"Weed\"]").is_err());
+    }
+
+    #[test]
+    fn a_place_sells_its_own_drugs_at_its_own_prices() {
+        let map = Map::from_toml(
+            "name = \"TEST\"\n[[location]]\nname = \"BRONX\"\n\
+             [[location]]\nname = \"DOCKS\"\ndrugs = [\"cocaine\", \"WEED\"]\n\
+             prices = { Cocaine = 0.5 }\n",
+        )
+        .unwrap();
+        let catalog = Catalog::classic();
+        let (cocaine, heroin) = (
+            catalog.find("COCAINE").unwrap(),
+            catalog.find("HEROIN").unwrap(),
+        );
+        let (bronx, docks) = (map.from_key('1').unwrap(), map.from_key('2').unwrap());
+
+        assert!(map.check(&catalog).is_ok());
+        assert!(map.sells(bronx, &catalog, heroin));
+        assert!(map.sells(docks, &catalog, cocaine));
+        assert!(!map.sells(docks, &catalog, heroin));
+        assert_eq!(map.price_multiplier(docks, &catalog, cocaine), 0.5);
+        assert_eq!(map.price_multiplier(bronx, &catalog, cocaine), 1.0);
+        assert_eq!(map.spec(bronx).police, 100);
+        assert_eq!(map.from_key('3'), None);
+        assert_eq!(map.from_key('0'), None);
+    }
+
+    #[test]
+    fn a_bad_map_is_turned_away() {
+        assert!(Map::from_toml("name = \"EMPTY\"\n").is_err());
+        let ten = "[[location]]\nname = \"X\"\n".repeat(10);
+        assert!(Map::from_toml(&format!("name = \"BIG\"\n{ten}")).is_err());
+        assert!(bronx("drugs = [\"GLUE\"]").is_err());
+        assert!(bronx("prices = { GLUE = 2.0 }").is_err());
+        assert!(bronx("prices = { WEED = 0.0 }").is_err());
+        assert!(bronx("police = -1").is_err());
+    }
+}
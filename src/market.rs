@@ -0,0 +1,431 @@
+// market.rs 🦀
+// what every drug costs in every location & how that moves day to day
+
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::catalog::Catalog;
+use crate::drug::Drug;
+use crate::location::Location;
+use crate::map::Map;
+
+// how much of the way back to its usual level a price goes in a day
+const REVERT: f64 = 0.25;
+// the most a price wanders on its own in a day, up or down
+const DRIFT: f64 = 0.15;
+// units dealt at once that move a price by a factor of e
+const DEPTH: f64 = 250.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Market {
+    // fresh prices every time you land, like the original
+    #[default]
+    Classic,
+    // every location keeps its prices, they wander, drift back toward
+    // the middle of their range & move with what you deal
+    Drifting,
+}
+
+impl Market {
+    pub const ALL: [Market; 2] = [Market::Classic, Market::Drifting];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Market::Classic => "classic",
+            Market::Drifting => "drifting",
+        }
+    }
+}
+
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Market {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Market::ALL
+            .into_iter()
+            .find(|market| market.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("no market called {s}, try classic or drifting"))
+    }
+}
+
+// what the prices in a place were on a day, or will be, from the informant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sighting {
+    pub day: i64,
+    // like the catalog, 0 where a drug isn't sold
+    pub prices: Vec<i64>,
+}
+
+impl Sighting {
+    pub fn price(&self, drug: Drug) -> i64 {
+        self.prices[drug.0]
+    }
+
+    pub(crate) fn fits(&self, drugs: usize) -> bool {
+        self.prices.len() == drugs
+    }
+}
+
+// how much of a drug a place has left to sell & will still buy today
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stock {
+    pub supply: i64,
+    pub demand: i64,
+}
+
+// tomorrow in one place, already told by the informant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Forecast {
+    location: Location,
+    levels: Vec<f64>,
+    stock: Vec<Option<Stock>>,
+}
+
+// a price is kept as a level that trades & drift nudge a little at a
+// time, what you pay is the level rounded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prices {
+    market: Market,
+    // [location][drug], 0 where a drug isn't sold
+    levels: Vec<Vec<f64>>,
+    // drugs run short, some days there's none & there's only so much
+    limited: bool,
+    // [location][drug], rolled where you land, none where there's none today
+    stock: Vec<Vec<Option<Stock>>>,
+    forecast: Option<Forecast>,
+}
+
+impl Prices {
+    pub fn new(market: Market, limited: bool, locations: usize, drugs: usize) -> Self {
+        Prices {
+            market,
+            levels: vec![vec![0.0; drugs]; locations],
+            limited,
+            stock: vec![vec![None; drugs]; locations],
+            forecast: None,
+        }
+    }
+
+    pub fn market(&self) -> Market {
+        self.market
+    }
+
+    // a price & a stock for every drug in every place, like new made them
+    pub(crate) fn fits(&self, locations: usize, drugs: usize) -> bool {
+        let grid =
+            |lens: Vec<usize>| lens.len() == locations && lens.iter().all(|len| *len == drugs);
+        let forecast = self.forecast.as_ref().is_none_or(|forecast| {
+            forecast.location.0 < locations
+                && forecast.levels.len() == drugs
+                && forecast.stock.len() == drugs
+        });
+        grid(self.levels.iter().map(Vec::len).collect())
+            && grid(self.stock.iter().map(Vec::len).collect())
+            && forecast
+    }
+
+    pub fn get(&self, location: Location, drug: Drug) -> i64 {
+        self.levels[location.0][drug.0].round() as i64
+    }
+
+    // every drug's price in a place, 0 where there's none today
+    pub fn row(&self, location: Location) -> Vec<i64> {
+        let mut prices = Self::rounded(&self.levels[location.0]);
+        for (drug, price) in prices.iter_mut().enumerate() {
+            if !self.has(location, Drug(drug)) {
+                *price = 0;
+            }
+        }
+        prices
+    }
+
+    fn rounded(levels: &[f64]) -> Vec<i64> {
+        levels.iter().map(|level| level.round() as i64).collect()
+    }
+
+    pub fn has_forecast(&self) -> bool {
+        self.forecast.is_some()
+    }
+
+    pub fn limited(&self) -> bool {
+        self.limited
+    }
+
+    // whether there's any of a drug today, there always is when drugs
+    // aren't limited
+    pub fn has(&self, location: Location, drug: Drug) -> bool {
+        !self.limited || self.stock[location.0][drug.0].is_some()
+    }
+
+    // what's left today, none when drugs aren't limited or there's none
+    pub fn stock(&self, location: Location, drug: Drug) -> Option<Stock> {
+        self.stock[location.0][drug.0].filter(|_| self.limited)
+    }
+
+    // the first day, a classic market only needs prices where you are
+    pub fn open(
+        &mut self,
+        location: Location,
+        map: &Map,
+        catalog: &Catalog,
+        rng: &mut ChaCha12Rng,
+    ) {
+        match self.market {
+            Market::Classic => self.roll(location, map, catalog, rng),
+            Market::Drifting => {
+                for location in map.locations() {
+                    self.roll(location, map, catalog, rng);
+                }
+            }
+        }
+        self.stock_up(location, map, catalog, rng);
+    }
+
+    // a day goes by & you land in location, where the informant said
+    // the prices would be is kept to
+    pub fn next_day(
+        &mut self,
+        location: Location,
+        map: &Map,
+        catalog: &Catalog,
+        rng: &mut ChaCha12Rng,
+    ) {
+        let forecast = self.forecast.take();
+        match self.market {
+            Market::Classic => self.roll(location, map, catalog, rng),
+            Market::Drifting => {
+                for location in map.locations() {
+                    self.drift(location, map, catalog, rng);
+                }
+            }
+        }
+        self.stock_up(location, map, catalog, rng);
+        if let Some(forecast) = forecast {
+            self.levels[forecast.location.0] = forecast.levels;
+            self.stock[forecast.location.0] = forecast.stock;
+        }
+    }
+
+    // works out tomorrow's prices in a place ahead of time
+    pub fn forecast(
+        &mut self,
+        location: Location,
+        map: &Map,
+        catalog: &Catalog,
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<i64> {
+        let today = self.levels[location.0].clone();
+        let stock = self.stock[location.0].clone();
+        match self.market {
+            Market::Classic => self.roll(location, map, catalog, rng),
+            Market::Drifting => self.drift(location, map, catalog, rng),
+        }
+        self.stock_up(location, map, catalog, rng);
+        let prices = self.row(location);
+        self.forecast = Some(Forecast {
+            location,
+            levels: std::mem::replace(&mut self.levels[location.0], today),
+            stock: std::mem::replace(&mut self.stock[location.0], stock),
+        });
+        prices
+    }
+
+    // anywhere between the drug's min & max, in steps
+    fn roll(&mut self, location: Location, map: &Map, catalog: &Catalog, rng: &mut ChaCha12Rng) {
+        for drug in catalog.drugs() {
+            let spec = catalog.spec(drug);
+            let (min, max, step) = (spec.min_price, spec.max_price, spec.step);
+            let price = rng.gen_range(min / step..=max / step) * step;
+            self.levels[location.0][drug.0] = if map.sells(location, catalog, drug) {
+                (price as f64 * map.price_multiplier(location, catalog, drug)).round()
+            } else {
+                0.0
+            };
+        }
+    }
+
+    fn drift(&mut self, location: Location, map: &Map, catalog: &Catalog, rng: &mut ChaCha12Rng) {
+        for drug in catalog.drugs() {
+            if !map.sells(location, catalog, drug) {
+                continue;
+            }
+            let spec = catalog.spec(drug);
+            let multiplier = map.price_multiplier(location, catalog, drug);
+            let min = spec.min_price as f64 * multiplier;
+            let max = spec.max_price as f64 * multiplier;
+            let level = &mut self.levels[location.0][drug.0];
+            let wander = rng.gen_range(-DRIFT..=DRIFT);
+            *level += ((min + max) / 2.0 - *level) * REVERT;
+            *level = (*level * (1.0 + wander)).clamp(min / 4.0, max * 4.0);
+        }
+    }
+
+    // what a place has for the day, a drug's scarcity is the chance
+    // there's none at all
+    fn stock_up(
+        &mut self,
+        location: Location,
+        map: &Map,
+        catalog: &Catalog,
+        rng: &mut ChaCha12Rng,
+    ) {
+        if !self.limited {
+            return;
+        }
+        for drug in catalog.drugs() {
+            let spec = catalog.spec(drug);
+            self.stock[location.0][drug.0] =
+                if !map.sells(location, catalog, drug) || rng.gen_range(0..100) < spec.scarcity {
+                    None
+                } else {
+                    Some(Stock {
+                        supply: rng.gen_range(spec.supply / 4..=spec.supply).max(1),
+                        demand: rng.gen_range(spec.demand / 4..=spec.demand).max(1),
+                    })
+                };
+        }
+    }
+
+    // a price spike or a crash
+    pub fn scale(&mut self, location: Location, drug: Drug, multiply: i64, divide: i64) {
+        let level = &mut self.levels[location.0][drug.0];
+        *level = match self.market {
+            Market::Classic => ((*level as i64).saturating_mul(multiply) / divide) as f64,
+            Market::Drifting => *level * multiply as f64 / divide as f64,
+        };
+    }
+
+    // where the level ends up after a deal, amount is negative for a sale
+    fn after(&self, location: Location, drug: Drug, amount: i64) -> f64 {
+        self.levels[location.0][drug.0] * (amount as f64 / DEPTH).exp()
+    }
+
+    // a drifting deal goes through halfway between the price before it &
+    // after it, so buying & selling the same lot back never pays
+    pub fn cost(&self, location: Location, drug: Drug, amount: i64) -> i64 {
+        match self.market {
+            Market::Classic => amount.saturating_mul(self.get(location, drug)),
+            Market::Drifting => {
+                let level = self.levels[location.0][drug.0];
+                let after = self.after(location, drug, amount);
+                (amount as f64 * (level + after) / 2.0).ceil() as i64
+            }
+        }
+    }
+
+    pub fn takings(&self, location: Location, drug: Drug, amount: i64) -> i64 {
+        match self.market {
+            Market::Classic => amount.saturating_mul(self.get(location, drug)),
+            Market::Drifting => {
+                let level = self.levels[location.0][drug.0];
+                let after = self.after(location, drug, -amount);
+                (amount as f64 * (level + after) / 2.0).floor() as i64
+            }
+        }
+    }
+
+    // the most cash buys here
+    pub fn afford(&self, location: Location, drug: Drug, cash: i64) -> i64 {
+        let price = self.get(location, drug);
+        if price <= 0 {
+            return 0;
+        }
+        let (mut lo, mut hi) = (0, cash / price);
+        while lo < hi {
+            let mid = (lo + hi + 1) / 2;
+            if self.cost(location, drug, mid) <= cash {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        lo
+    }
+
+    // buying pushes the price up & takes from the supply, selling pushes
+    // it down & takes from the demand
+    pub fn deal(&mut self, location: Location, drug: Drug, amount: i64) {
+        if self.market == Market::Drifting {
+            self.levels[location.0][drug.0] = self.after(location, drug, amount);
+        }
+        if let Some(stock) = &mut self.stock[location.0][drug.0] {
+            if amount > 0 {
+                stock.supply -= amount;
+            } else {
+                stock.demand += amount;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn opened(market: Market, limited: bool) -> (Prices, Map, Catalog, ChaCha12Rng) {
+        let (map, catalog) = (Map::classic(), Catalog::classic());
+        let mut rng = ChaCha12Rng::seed_from_u64(7);
+        let mut prices = Prices::new(market, limited, map.len(), catalog.len());
+        prices.open(map.start(), &map, &catalog, &mut rng);
+        (prices, map, catalog, rng)
+    }
+
+    #[test]
+    fn drifting_prices_stay_in_bounds() {
+        let (mut prices, map, catalog, mut rng) = opened(Market::Drifting, false);
+        for _ in 0..1000 {
+            prices.next_day(map.start(), &map, &catalog, &mut rng);
+            for location in map.locations() {
+                for drug in catalog.drugs() {
+                    let spec = catalog.spec(drug);
+                    let price = prices.get(location, drug);
+                    assert!(price >= spec.min_price / 4 && price <= spec.max_price * 4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_drifting_deal_moves_the_price() {
+        let (mut prices, map, catalog, _) = opened(Market::Drifting, false);
+        let (here, drug) = (map.start(), catalog.find("COCAINE").unwrap());
+        let before = prices.get(here, drug);
+        let paid = prices.cost(here, drug, 50);
+        prices.deal(here, drug, 50);
+        assert!(prices.get(here, drug) > before);
+        // selling the lot straight back loses money
+        assert!(prices.takings(here, drug, 50) < paid);
+        prices.deal(here, drug, -50);
+        assert_eq!(prices.get(here, drug), before);
+    }
+
+    #[test]
+    fn a_limited_deal_uses_up_the_stock() {
+        let (mut prices, map, catalog, _) = opened(Market::Classic, true);
+        let here = map.start();
+        let drug = catalog
+            .drugs()
+            .find(|drug| prices.has(here, *drug))
+            .unwrap();
+        let Stock { supply, demand } = prices.stock(here, drug).unwrap();
+        prices.deal(here, drug, 1);
+        prices.deal(here, drug, -1);
+        let stock = prices.stock(here, drug).unwrap();
+        assert_eq!((stock.supply, stock.demand), (supply - 1, demand - 1));
+
+        let (prices, ..) = opened(Market::Classic, false);
+        assert!(prices.has(here, drug));
+        assert_eq!(prices.stock(here, drug), None);
+    }
+}
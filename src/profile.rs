@@ -0,0 +1,268 @@
+// profile.rs 🦀
+// lifetime stats for every player, kept in profiles.json next to the scores
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::achievement::Achievement;
+use crate::dirs;
+use crate::game::Game;
+use crate::lock::FileLock;
+
+pub const PROFILES_VERSION: u32 = 1;
+
+// drugs & locations go by name, games can be played with any catalog or map
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub games: i64,
+    pub total_score: i64,
+    pub best_score: i64,
+    pub best_money: i64,
+    pub bought: HashMap<String, i64>,
+    pub sold: HashMap<String, i64>,
+    pub visits: HashMap<String, i64>,
+    pub cops_killed: i64,
+    pub times_mugged: i64,
+    pub doctor_bills: i64,
+    pub achievements: Vec<Achievement>,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Profile {
+            name: name.to_string(),
+            ..Profile::default()
+        }
+    }
+
+    pub fn average_score(&self) -> i64 {
+        if self.games > 0 {
+            self.total_score / self.games
+        } else {
+            0
+        }
+    }
+
+    pub fn favorite_location(&self) -> Option<&str> {
+        self.visits
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn add_game(&mut self, game: &Game) {
+        let score = game.score();
+        let stats = game.stats();
+        if self.games == 0 || score.total_money > self.best_money {
+            self.best_money = score.total_money;
+        }
+        self.games += 1;
+        self.total_score += score.score;
+        self.best_score = self.best_score.max(score.score);
+
+        for drug in game.catalog().drugs() {
+            let name = game.catalog().name(drug);
+            *self.bought.entry(name.to_string()).or_insert(0) += stats.bought[drug.index()];
+            *self.sold.entry(name.to_string()).or_insert(0) += stats.sold[drug.index()];
+        }
+        for location in game.map().locations() {
+            let name = game.map().name(location);
+            *self.visits.entry(name.to_string()).or_insert(0) += stats.visits[location.index()];
+        }
+        self.cops_killed += stats.cops_killed;
+        self.times_mugged += stats.mugged;
+        self.doctor_bills += stats.doctor_bills;
+        for achievement in game.achievements() {
+            if !self.achievements.contains(achievement) {
+                self.achievements.push(*achievement);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    // whoever played last, picked again next time
+    pub last: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    pub fn find(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    fn find_or_add(&mut self, name: &str) -> &mut Profile {
+        match self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+        {
+            Some(i) => &mut self.profiles[i],
+            None => {
+                self.profiles.push(Profile::new(name));
+                self.profiles.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+// read on its own first, a newer game's file might not parse as ours
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfileFile {
+    version: u32,
+    #[serde(flatten)]
+    profiles: Profiles,
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("profiles.json"))
+}
+
+pub fn load(path: &Path) -> io::Result<Profiles> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Profiles::default()),
+        Err(e) => return Err(e),
+    };
+    let Version { version } = serde_json::from_str(&json)?;
+    if version > PROFILES_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("unsupported profiles version {version}"),
+        ));
+    }
+    let file: ProfileFile = serde_json::from_str(&json)?;
+    Ok(file.profiles)
+}
+
+// load, change & write back under the lock, like the high scores
+fn update<T>(path: &Path, change: impl FnOnce(&mut Profiles) -> T) -> io::Result<T> {
+    let _lock = FileLock::acquire(&path.with_extension("lock"))?;
+
+    // a file that won't parse is kept as profiles.json.bad & everyone
+    // starts over, a newer game's file is left alone
+    let mut profiles = match load(path) {
+        Ok(profiles) => profiles,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof
+            ) =>
+        {
+            fs::rename(path, path.with_extension("json.bad"))?;
+            Profiles::default()
+        }
+        Err(e) => return Err(e),
+    };
+    let result = change(&mut profiles);
+    let json = serde_json::to_string_pretty(&ProfileFile {
+        version: PROFILES_VERSION,
+        profiles,
+    })?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)?;
+    Ok(result)
+}
+
+// makes name the current player, creating the profile if it's new
+pub fn select(path: &Path, name: &str) -> io::Result<Profile> {
+    update(path, |profiles| {
+        profiles.last = Some(name.to_string());
+        profiles.find_or_add(name).clone()
+    })
+}
+
+pub fn record(path: &Path, name: &str, game: &Game) -> io::Result<Profile> {
+    update(path, |profiles| {
+        profiles.last = Some(name.to_string());
+        let profile = profiles.find_or_add(name);
+        profile.add_game(game);
+        profile.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Command;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("drugwars-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // a weed bought, then given up on
+    fn game() -> Game {
+        let mut game = Game::with_seed(7);
+        let weed = game.catalog().find("WEED").unwrap();
+        game.apply(Command::Buy {
+            drug: weed,
+            amount: 1,
+        })
+        .unwrap();
+        game.apply(Command::Forfeit).unwrap();
+        game
+    }
+
+    #[test]
+    fn every_game_adds_up() {
+        let game = game();
+        let mut profile = Profile::new("TEST");
+        profile.add_game(&game);
+        profile.add_game(&game);
+
+        let score = game.score();
+        assert_eq!(profile.games, 2);
+        assert_eq!(profile.total_score, 2 * score.score);
+        assert_eq!(profile.best_money, score.total_money);
+        assert_eq!(profile.bought["WEED"], 2);
+        assert_eq!(profile.sold["WEED"], 0);
+        assert_eq!(profile.visits["BRONX"], 2);
+        assert_eq!(profile.favorite_location(), Some("BRONX"));
+    }
+
+    #[test]
+    fn a_broken_file_is_kept_aside() {
+        let dir = scratch("broken-profiles");
+        let path = dir.join("profiles.json");
+        fs::write(&path, "{ \"version\": 1, \"profiles\": [").unwrap();
+
+        let profile = record(&path, "TEST", &game()).unwrap();
+        assert_eq!(profile.games, 1);
+        assert!(dir.join("profiles.json.bad").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_newer_file_is_left_alone() {
+        let dir = scratch("newer-profiles");
+        let path = dir.join("profiles.json");
+        let newer = format!(
+            "{{ \"version\": {}, \"players\": {{}} }}",
+            PROFILES_VERSION + 1
+        );
+        fs::write(&path, &newer).unwrap();
+
+        let e = load(&path).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::Unsupported);
+        assert!(e.to_string().contains("unsupported profiles version"));
+        assert!(record(&path, "TEST", &game()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        assert!(!dir.join("profiles.json.bad").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,254 @@
+// rules.rs 🦀
+// the numbers the game is played by & the difficulty presets
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::market::Market;
+
+// a year, longer than any game needs
+pub const MAX_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Classic,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Classic,
+        Difficulty::Hard,
+        Difficulty::Custom,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Classic => "classic",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("no difficulty called {s}, try easy, classic or hard"))
+    }
+}
+
+fn custom() -> Difficulty {
+    Difficulty::Custom
+}
+
+fn thousand() -> i64 {
+    1000
+}
+
+// interest is a percent added every day, you're wasted at max_damage,
+// the informant charges a thousand, the market is classic & there's
+// always enough of every drug unless it says otherwise
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rules {
+    #[serde(default = "custom")]
+    pub difficulty: Difficulty,
+    pub cash: i64,
+    pub debt: i64,
+    pub max_loan: i64,
+    pub days: i64,
+    pub hold: i64,
+    pub debt_interest: i64,
+    pub bank_interest: i64,
+    pub max_damage: i64,
+    #[serde(default = "thousand")]
+    pub informant: i64,
+    #[serde(default)]
+    pub market: Market,
+    #[serde(default)]
+    pub limited: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Rules {
+    pub fn classic() -> Self {
+        Rules {
+            difficulty: Difficulty::Classic,
+            cash: 2000,
+            debt: 5500,
+            max_loan: 9450,
+            days: 31,
+            hold: 100,
+            debt_interest: 10,
+            bank_interest: 5,
+            max_damage: 50,
+            informant: 1000,
+            market: Market::Classic,
+            limited: false,
+        }
+    }
+
+    pub fn easy() -> Self {
+        Rules {
+            difficulty: Difficulty::Easy,
+            cash: 5000,
+            debt: 2000,
+            max_loan: 15000,
+            days: 45,
+            hold: 150,
+            debt_interest: 5,
+            bank_interest: 7,
+            max_damage: 75,
+            informant: 500,
+            market: Market::Classic,
+            limited: false,
+        }
+    }
+
+    pub fn hard() -> Self {
+        Rules {
+            difficulty: Difficulty::Hard,
+            cash: 1000,
+            debt: 7500,
+            max_loan: 9450,
+            days: 25,
+            hold: 80,
+            debt_interest: 15,
+            bank_interest: 3,
+            max_damage: 30,
+            informant: 2000,
+            market: Market::Classic,
+            limited: false,
+        }
+    }
+
+    // custom rules only come from a file
+    pub fn preset(difficulty: Difficulty) -> Option<Self> {
+        match difficulty {
+            Difficulty::Easy => Some(Self::easy()),
+            Difficulty::Classic => Some(Self::classic()),
+            Difficulty::Hard => Some(Self::hard()),
+            Difficulty::Custom => None,
+        }
+    }
+
+    // a rules file is always custom, whatever it says, so its scores
+    // never get mixed in with a preset's
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut rules = Self::from_toml(&fs::read_to_string(path)?)?;
+        rules.difficulty = Difficulty::Custom;
+        Ok(rules)
+    }
+
+    pub fn from_toml(text: &str) -> io::Result<Self> {
+        let rules: Rules = toml::from_str(text)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub(crate) fn validate(&self) -> io::Result<()> {
+        let bad = |msg: &str| Err(io::Error::new(io::ErrorKind::InvalidData, msg.to_string()));
+
+        if self.cash < 0 || self.debt < 0 || self.hold < 0 || self.informant < 0 {
+            return bad("cash, debt, hold & the informant can't be negative");
+        }
+        if self.max_loan < self.debt {
+            return bad("max_loan can't be less than the debt");
+        }
+        if !(1..=MAX_DAYS).contains(&self.days) {
+            return bad("the game needs 1 to 365 days");
+        }
+        if self.debt_interest < 0 || self.bank_interest < 0 {
+            return bad("interest can't be negative");
+        }
+        if self.max_damage < 1 {
+            return bad("max_damage must be positive");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSIC: &str = include_str!("../data/rules.toml");
+
+    #[test]
+    fn the_presets_are_what_they_say() {
+        for difficulty in Difficulty::ALL {
+            match Rules::preset(difficulty) {
+                Some(rules) => {
+                    assert_eq!(rules.difficulty, difficulty);
+                    assert!(rules.validate().is_ok(), "{difficulty}");
+                }
+                None => assert_eq!(difficulty, Difficulty::Custom),
+            }
+        }
+        let (easy, classic, hard) = (Rules::easy(), Rules::classic(), Rules::hard());
+        assert!(easy.cash > classic.cash && classic.cash > hard.cash);
+        assert!(easy.days > classic.days && classic.days > hard.days);
+        assert!(
+            easy.debt_interest < classic.debt_interest
+                && classic.debt_interest < hard.debt_interest
+        );
+    }
+
+    // the file that ships is the classic preset, apart from the name
+    #[test]
+    fn the_rules_file_is_the_classic_rules() {
+        let rules = Rules::from_toml(CLASSIC).unwrap();
+        assert_eq!(rules.difficulty, Difficulty::Custom);
+        assert_eq!(
+            Rules {
+                difficulty: Difficulty::Classic,
+                ..rules
+            },
+            Rules::classic()
+        );
+    }
+
+    #[test]
+    fn bad_rules_are_turned_away() {
+        let changed = |from: &str, to: &str| Rules::from_toml(&CLASSIC.replace(from, to));
+        for days in ["days = 1", "days = 365"] {
+            assert!(changed("days = 31", days).is_ok(), "{days}");
+        }
+        for (from, to) in [
+            ("days = 31", "days = 0"),
+            ("days = 31", "days = 366"),
+            ("cash = 2000", "cash = -1"),
+            ("max_loan = 9450", "max_loan = 5000"),
+            ("debt_interest = 10", "debt_interest = -1"),
+            ("max_damage = 50", "max_damage = 0"),
+            ("market = \"classic\"", "market = \"rising\""),
+        ] {
+            assert!(changed(from, to).is_err(), "{to}");
+        }
+    }
+}
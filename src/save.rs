@@ -0,0 +1,162 @@
+// save.rs 🦀
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::dirs;
+use crate::game::Game;
+
+pub const SAVE_VERSION: u32 = 14;
+
+#[derive(Serialize)]
+struct SaveOut<'a> {
+    version: u32,
+    game: &'a Game,
+}
+
+// read on its own first, an older game won't parse as a game at all
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct SaveIn {
+    game: Game,
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("save.json"))
+}
+
+pub fn save(game: &Game, path: &Path) -> io::Result<()> {
+    if !game.can_save() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "can't save in the middle of a deal or a fight",
+        ));
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(&SaveOut {
+        version: SAVE_VERSION,
+        game,
+    })?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, path)
+}
+
+pub fn load(path: &Path) -> io::Result<Game> {
+    let json = fs::read_to_string(path)?;
+    let Version { version } = serde_json::from_str(&json)?;
+    if version != SAVE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("save version {version} can't be loaded, older saves aren't supported"),
+        ));
+    }
+    let save: SaveIn = serde_json::from_str(&json)?;
+    save.game.validate()?;
+    Ok(save.game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Command;
+    use crate::location::Location;
+    use serde_json::json;
+
+    // turn down offers & run from fights until the game can be saved
+    fn settle(game: &mut Game) {
+        while !game.can_save() && !game.is_over() {
+            let command = if game.in_fight() {
+                Command::Run
+            } else {
+                Command::Decline
+            };
+            game.apply(command).unwrap();
+        }
+    }
+
+    // back & forth between the first two places
+    fn travel(game: &mut Game, days: usize) {
+        for _ in 0..days {
+            let there = Location(1 - game.location().index());
+            game.apply(Command::Jet(there)).unwrap();
+            settle(game);
+        }
+    }
+
+    // a value, not a string, so maps compare whatever order they're in
+    fn state(game: &Game) -> serde_json::Value {
+        serde_json::to_value(game).unwrap()
+    }
+
+    #[test]
+    fn a_save_plays_on_like_the_game() {
+        let path = std::env::temp_dir().join(format!("drugwars-save-{}.json", std::process::id()));
+        let mut saved = Game::with_seed(7);
+        let mut unsaved = Game::with_seed(7);
+        for game in [&mut saved, &mut unsaved] {
+            game.start();
+            settle(game);
+            travel(game, 3);
+        }
+
+        save(&saved, &path).unwrap();
+        let mut loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(state(&saved), state(&loaded));
+
+        for game in [&mut saved, &mut unsaved, &mut loaded] {
+            travel(game, 5);
+        }
+        assert_eq!(state(&saved), state(&unsaved));
+        assert_eq!(state(&loaded), state(&unsaved));
+    }
+
+    // a save with one thing in it changed, where a json pointer points
+    fn tampered(pointer: &str, value: serde_json::Value) -> io::Result<Game> {
+        let name = pointer.replace('/', "-");
+        let path = std::env::temp_dir().join(format!("drugwars{name}-{}.json", std::process::id()));
+        let mut game = Game::with_seed(7);
+        game.start();
+        settle(&mut game);
+        save(&game, &path).unwrap();
+        let mut json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        *json.pointer_mut(pointer).unwrap() = value;
+        fs::write(&path, json.to_string()).unwrap();
+        let loaded = load(&path);
+        fs::remove_file(&path).unwrap();
+        loaded
+    }
+
+    // a save that's been tinkered with is turned away, not played
+    #[test]
+    fn a_bad_save_is_an_error() {
+        assert!(tampered("/game/location", json!(0)).is_ok());
+        assert!(tampered("/game/location", json!(99)).is_err());
+        assert!(tampered("/game/stash", json!([1])).is_err());
+        assert!(tampered("/game/prices/levels/0", json!([])).is_err());
+        assert!(tampered("/game/seen", json!([])).is_err());
+        assert!(tampered(
+            "/game/history/seen",
+            json!([[99, {"day": 0, "prices": []}]])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn an_older_save_says_so() {
+        match tampered("/version", json!(12)) {
+            Err(e) => assert!(e.to_string().contains("older saves")),
+            Ok(_) => panic!("an older save loaded"),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// score.rs 🦀
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rank {
+    GangstaMotherfucker,
+    HustlerFucker,
+    PabloEscobar,
+    Kingpin,
+    RunTheTown,
+    OwnTheBlock,
+    SmallTimePusha,
+}
+
+impl Rank {
+    pub fn from_score(score: i64) -> Rank {
+        match score {
+            100 => Rank::GangstaMotherfucker,
+            99 => Rank::HustlerFucker,
+            98 => Rank::PabloEscobar,
+            76..=97 => Rank::Kingpin,
+            51..=75 => Rank::RunTheTown,
+            31..=50 => Rank::OwnTheBlock,
+            _ => Rank::SmallTimePusha,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rank::GangstaMotherfucker => "GANGSTA MOTHERFUCKER",
+            Rank::HustlerFucker => "HUSTLER FUCKER",
+            Rank::PabloEscobar => "PABLO ESCOBAR",
+            Rank::Kingpin => "KINGPIN",
+            Rank::RunTheTown => "RUN THE TOWN",
+            Rank::OwnTheBlock => "OWN THE BLOCK",
+            Rank::SmallTimePusha => "SMALL TIME PUSHA",
+        }
+    }
+
+    pub fn motto(&self) -> &'static str {
+        match self {
+            Rank::GangstaMotherfucker => "YOU ARE MY HERO",
+            Rank::HustlerFucker => "YOU THA DOPE MAN",
+            Rank::PabloEscobar => "YOU ARE A GOD",
+            Rank::Kingpin => "GOD DAMN",
+            Rank::RunTheTown => "PRETTY GOOD",
+            Rank::OwnTheBlock => "NOT BAD",
+            Rank::SmallTimePusha => "WEAK",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub total_money: i64,
+    pub score: i64,
+    pub rank: Rank,
+}
+
+impl Score {
+    pub fn new(total_money: i64) -> Self {
+        let score = if total_money > 50_000_000 {
+            100
+        } else if total_money >= 25_000_000 {
+            99
+        } else if total_money >= 10_000_000 {
+            98
+        } else {
+            // below 10 million, & anything in the red scores 0 anyway
+            let calculated_score = (total_money.max(0) * 100) / 10_000_000;
+            calculated_score.clamp(0, 97)
+        };
+
+        Score {
+            total_money,
+            score,
+            rank: Rank::from_score(score),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// setup.rs 🦀
+// everything a new game is built from
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::Catalog;
+use crate::events::Events;
+use crate::map::Map;
+use crate::rules::Rules;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Setup {
+    pub rules: Rules,
+    pub catalog: Catalog,
+    pub map: Map,
+    pub events: Events,
+}
+
+impl Setup {
+    pub fn new(rules: Rules, catalog: Catalog, map: Map, events: Events) -> io::Result<Self> {
+        let setup = Setup {
+            rules,
+            catalog,
+            map,
+            events,
+        };
+        setup.validate()?;
+        Ok(setup)
+    }
+
+    // the files were checked when they were read, a setup from a journal
+    // or a save wasn't
+    pub fn validate(&self) -> io::Result<()> {
+        self.rules.validate()?;
+        self.catalog.validate()?;
+        self.map.validate()?;
+        self.events.validate()?;
+        self.map.check(&self.catalog)
+    }
+}
@@ -0,0 +1,35 @@
+// stats.rs 🦀
+// what happened over one game, added to the player's profile at the end
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// bought, sold & visits are indexed like the catalog & the map,
+// events are counted by name, muggings by what they do, any event
+// that takes your cash is one, whatever an events file calls it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub bought: Vec<i64>,
+    pub sold: Vec<i64>,
+    pub visits: Vec<i64>,
+    pub cops_killed: i64,
+    pub doctor_bills: i64,
+    #[serde(default)]
+    pub mugged: i64,
+    pub events: HashMap<String, i64>,
+}
+
+impl Stats {
+    pub fn new(drugs: usize, locations: usize) -> Self {
+        Stats {
+            bought: vec![0; drugs],
+            sold: vec![0; drugs],
+            visits: vec![0; locations],
+            ..Stats::default()
+        }
+    }
+
+    pub fn event(&self, name: &str) -> i64 {
+        self.events.get(name).copied().unwrap_or(0)
+    }
+}
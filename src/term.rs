@@ -0,0 +1,170 @@
+// term.rs 🦀
+// puts the terminal back the way the player had it, however the game ends
+
+use std::io;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::OnceLock;
+
+#[cfg(unix)]
+use nix::libc;
+#[cfg(unix)]
+use nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg, Termios};
+
+// colors off & the cursor back on
+const RESET: &[u8] = b"\x1B[0m\x1B[?25h";
+// & out of the tui's alternate screen
+const LEAVE: &[u8] = b"\x1B[0m\x1B[?25h\x1B[?1049l";
+
+// the settings from before the game touched them, a plain libc::termios so
+// a signal handler can read it
+#[cfg(unix)]
+static ORIGINAL: OnceLock<libc::termios> = OnceLock::new();
+
+static ALTERNATE: AtomicBool = AtomicBool::new(false);
+
+// raw keys for as long as it's alive, the old settings come back on drop,
+// even when the read fails
+#[cfg(unix)]
+pub struct RawMode {
+    old: Termios,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    pub fn enter() -> io::Result<Self> {
+        let old = tcgetattr(libc::STDIN_FILENO)?;
+        let mut raw = old.clone();
+        cfmakeraw(&mut raw);
+        tcsetattr(libc::STDIN_FILENO, SetArg::TCSANOW, &raw)?;
+        Ok(RawMode { old })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = tcsetattr(libc::STDIN_FILENO, SetArg::TCSANOW, &self.old);
+    }
+}
+
+#[cfg(windows)]
+pub struct RawMode {
+    handle: winapi::um::winnt::HANDLE,
+    old: winapi::shared::minwindef::DWORD,
+}
+
+#[cfg(windows)]
+impl RawMode {
+    pub fn enter() -> io::Result<Self> {
+        use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+        use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+        use winapi::um::processenv::GetStdHandle;
+        use winapi::um::winbase::STD_INPUT_HANDLE;
+        use winapi::um::wincon::{ENABLE_ECHO_INPUT, ENABLE_LINE_INPUT, ENABLE_WINDOW_INPUT};
+
+        unsafe {
+            let handle = GetStdHandle(STD_INPUT_HANDLE);
+            if handle == INVALID_HANDLE_VALUE {
+                return Err(io::Error::last_os_error());
+            }
+            let mut old = 0;
+            if GetConsoleMode(handle, &mut old) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let raw = (old & !(ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT)) | ENABLE_WINDOW_INPUT;
+            if SetConsoleMode(handle, raw) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(RawMode { handle, old })
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            winapi::um::consoleapi::SetConsoleMode(self.handle, self.old);
+        }
+    }
+}
+
+// whether more keys are already on their way, an escape on its own is the
+// esc key, one followed by more bytes is an arrow or a function key
+#[cfg(unix)]
+pub fn pending(ms: i32) -> bool {
+    use nix::poll::{poll, PollFd, PollFlags};
+
+    let mut fds = [PollFd::new(libc::STDIN_FILENO, PollFlags::POLLIN)];
+    poll(&mut fds, ms).is_ok_and(|n| n > 0)
+}
+
+// the tui says when it's on the alternate screen, so a crash leaves it
+#[cfg(feature = "tui")]
+pub fn alternate(on: bool) {
+    ALTERNATE.store(on, Ordering::Relaxed);
+}
+
+// remembers the terminal as it is now & makes sure a panic or a kill
+// hands it back like that
+pub fn install() {
+    #[cfg(unix)]
+    if let Ok(termios) = tcgetattr(libc::STDIN_FILENO) {
+        let _ = ORIGINAL.set(termios.into());
+    }
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+    }));
+
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+
+        let action = SigAction::new(SigHandler::Handler(bail), SaFlags::empty(), SigSet::empty());
+        for signal in [
+            Signal::SIGTERM,
+            Signal::SIGHUP,
+            Signal::SIGINT,
+            Signal::SIGQUIT,
+        ] {
+            unsafe {
+                let _ = sigaction(signal, &action);
+            }
+        }
+    }
+}
+
+// only async signal safe calls, it runs inside the handler too
+pub fn restore() {
+    let reset = if ALTERNATE.load(Ordering::Relaxed) {
+        LEAVE
+    } else {
+        RESET
+    };
+    #[cfg(unix)]
+    unsafe {
+        if let Some(termios) = ORIGINAL.get() {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, termios);
+        }
+        libc::write(libc::STDOUT_FILENO, reset.as_ptr().cast(), reset.len());
+    }
+    #[cfg(not(unix))]
+    {
+        use std::io::Write;
+
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(reset);
+        let _ = stdout.flush();
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn bail(signal: libc::c_int) {
+    restore();
+    unsafe { libc::_exit(128 + signal) }
+}
@@ -0,0 +1,312 @@
+// theme.rs 🦀
+// recolors what the game prints, so one set of colors suits any terminal
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    // the colors as written, truecolor & all
+    Classic,
+    // bold & nothing else
+    Monochrome,
+    // bright bold colors, nothing dark on the default background
+    HighContrast,
+    // truecolor squeezed into the 16 colors every terminal has
+    Ansi16,
+}
+
+pub const NAMES: &str = "classic, monochrome, high-contrast or 16-color";
+
+static THEME: AtomicU8 = AtomicU8::new(Theme::Classic as u8);
+
+// what a color comes down to, a terminal default, one of the 16 or rgb
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ink {
+    Default,
+    Ansi(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name.to_ascii_lowercase().as_str() {
+            "classic" => Some(Theme::Classic),
+            "monochrome" | "mono" => Some(Theme::Monochrome),
+            "high-contrast" => Some(Theme::HighContrast),
+            "16-color" | "16" => Some(Theme::Ansi16),
+            _ => None,
+        }
+    }
+
+    // NO_COLOR wins over the terminal, a terminal that doesn't say it does
+    // truecolor gets the 16 colors
+    pub fn detect() -> Theme {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Theme::Monochrome;
+        }
+        let truecolor = env::var("COLORTERM")
+            .is_ok_and(|value| matches!(value.as_str(), "truecolor" | "24bit"))
+            || env::var_os("WT_SESSION").is_some();
+        if truecolor {
+            Theme::Classic
+        } else {
+            Theme::Ansi16
+        }
+    }
+
+    pub fn ink(self, ink: Ink) -> Ink {
+        match (self, ink) {
+            (Theme::Classic, ink) | (_, ink @ Ink::Default) => ink,
+            (Theme::Monochrome, _) => Ink::Default,
+            (Theme::Ansi16, Ink::Rgb(r, g, b)) => Ink::Ansi(nearest(r, g, b)),
+            (Theme::Ansi16, ink) => ink,
+            (Theme::HighContrast, Ink::Rgb(r, g, b)) => Ink::Ansi(bright(nearest(r, g, b))),
+            (Theme::HighContrast, Ink::Ansi(n)) => Ink::Ansi(bright(n)),
+        }
+    }
+
+    fn bold(self) -> bool {
+        matches!(self, Theme::HighContrast)
+    }
+}
+
+pub fn set(theme: Theme) {
+    THEME.store(theme as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Theme {
+    match THEME.load(Ordering::Relaxed) {
+        1 => Theme::Monochrome,
+        2 => Theme::HighContrast,
+        3 => Theme::Ansi16,
+        _ => Theme::Classic,
+    }
+}
+
+// the parameters of one \x1B[...m, rewritten for the theme, an empty
+// string when nothing's left of it
+pub fn sgr(params: &str) -> String {
+    restyled(current(), params)
+}
+
+fn restyled(theme: Theme, params: &str) -> String {
+    let codes: Vec<u32> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut codes = codes.into_iter();
+    while let Some(code) = codes.next() {
+        let (ink, background) = match code {
+            30..=37 => (Ink::Ansi((code - 30) as u8), false),
+            90..=97 => (Ink::Ansi((code - 90 + 8) as u8), false),
+            40..=47 => (Ink::Ansi((code - 40) as u8), true),
+            100..=107 => (Ink::Ansi((code - 100 + 8) as u8), true),
+            38 | 48 => {
+                let ink = match codes.next() {
+                    Some(2) => {
+                        let mut rgb = [0; 3];
+                        for part in &mut rgb {
+                            *part = codes.next().unwrap_or(0).min(255) as u8;
+                        }
+                        Ink::Rgb(rgb[0], rgb[1], rgb[2])
+                    }
+                    Some(5) => indexed(codes.next().unwrap_or(0).min(255) as u8),
+                    _ => Ink::Default,
+                };
+                (ink, code == 48)
+            }
+            code => {
+                out.push(code.to_string());
+                continue;
+            }
+        };
+        let base = if background { 40 } else { 30 };
+        match theme.ink(ink) {
+            Ink::Default => continue,
+            Ink::Ansi(n) if n < 8 => out.push((base + n as u32).to_string()),
+            Ink::Ansi(n) => out.push((base + 60 + n as u32 - 8).to_string()),
+            Ink::Rgb(r, g, b) => out.push(format!("{};2;{r};{g};{b}", base + 8)),
+        }
+        if theme.bold() && !background {
+            out.push("1".to_string());
+        }
+    }
+    if out.is_empty() {
+        String::new()
+    } else {
+        format!("\x1B[{}m", out.join(";"))
+    }
+}
+
+// one of the 256, the 16 as they are, the 6x6x6 cube & the grays as
+// the rgb they stand for
+fn indexed(n: u8) -> Ink {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match n {
+        0..=15 => Ink::Ansi(n),
+        16..=231 => {
+            let n = (n - 16) as usize;
+            Ink::Rgb(LEVELS[n / 36], LEVELS[n / 6 % 6], LEVELS[n % 6])
+        }
+        _ => {
+            let gray = 8 + 10 * (n - 232);
+            Ink::Rgb(gray, gray, gray)
+        }
+    }
+}
+
+// the closest of the 16 by hue, grays by how light they are
+fn nearest(r: u8, g: u8, b: u8) -> u8 {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let light = if max > 200 { 8 } else { 0 };
+    if max - min < 48 {
+        return match max {
+            0..=63 => 0,
+            64..=159 => 8,
+            160..=223 => 7,
+            _ => 15,
+        };
+    }
+    let hue = match max {
+        _ if max == r => (60 * (g - b) / (max - min) + 360) % 360,
+        _ if max == g => 60 * (b - r) / (max - min) + 120,
+        _ => 60 * (r - g) / (max - min) + 240,
+    };
+    // red, yellow, green, cyan, blue, magenta around the wheel
+    let color = [1, 3, 2, 6, 4, 5][(((hue + 30) % 360) / 60) as usize];
+    color + light
+}
+
+// the light twin of a color, blue is hard to read even then so it's cyan
+fn bright(n: u8) -> u8 {
+    match n {
+        0 | 8 => 15,
+        4 | 12 => 14,
+        n if n < 8 => n + 8,
+        n => n,
+    }
+}
+
+// the tui's colors go the same way, after the frame is drawn
+#[cfg(feature = "tui")]
+pub fn restyle(buffer: &mut ratatui::buffer::Buffer) {
+    use ratatui::style::{Color, Modifier};
+
+    fn to_ink(color: Color) -> Ink {
+        match color {
+            Color::Reset => Ink::Default,
+            Color::Black => Ink::Ansi(0),
+            Color::Red => Ink::Ansi(1),
+            Color::Green => Ink::Ansi(2),
+            Color::Yellow => Ink::Ansi(3),
+            Color::Blue => Ink::Ansi(4),
+            Color::Magenta => Ink::Ansi(5),
+            Color::Cyan => Ink::Ansi(6),
+            Color::Gray => Ink::Ansi(7),
+            Color::DarkGray => Ink::Ansi(8),
+            Color::LightRed => Ink::Ansi(9),
+            Color::LightGreen => Ink::Ansi(10),
+            Color::LightYellow => Ink::Ansi(11),
+            Color::LightBlue => Ink::Ansi(12),
+            Color::LightMagenta => Ink::Ansi(13),
+            Color::LightCyan => Ink::Ansi(14),
+            Color::White => Ink::Ansi(15),
+            Color::Rgb(r, g, b) => Ink::Rgb(r, g, b),
+            Color::Indexed(n) => indexed(n),
+        }
+    }
+
+    fn from_ink(ink: Ink) -> Color {
+        match ink {
+            Ink::Default => Color::Reset,
+            Ink::Ansi(n) => Color::Indexed(n),
+            Ink::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        }
+    }
+
+    let theme = current();
+    if theme == Theme::Classic {
+        return;
+    }
+    for cell in &mut buffer.content {
+        let ink = theme.ink(to_ink(cell.fg));
+        if theme.bold() && ink != Ink::Default {
+            cell.modifier.insert(Modifier::BOLD);
+        }
+        cell.fg = from_ink(ink);
+        cell.bg = from_ink(theme.ink(to_ink(cell.bg)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn each(theme: Theme, cases: &[(&str, &str)]) {
+        for (params, expected) in cases {
+            assert_eq!(restyled(theme, params), *expected, "{theme:?} {params}");
+        }
+    }
+
+    #[test]
+    fn classic_keeps_the_colors_as_written() {
+        each(
+            Theme::Classic,
+            &[
+                ("0", "\x1B[0m"),
+                ("1;31", "\x1B[1;31m"),
+                ("38;2;255;128;0", "\x1B[38;2;255;128;0m"),
+                ("38;5;9", "\x1B[91m"),
+                ("38;5;196", "\x1B[38;2;255;0;0m"),
+                ("48;5;244", "\x1B[48;2;128;128;128m"),
+            ],
+        );
+    }
+
+    #[test]
+    fn monochrome_drops_every_color() {
+        each(
+            Theme::Monochrome,
+            &[
+                ("31", ""),
+                ("1;31", "\x1B[1m"),
+                ("38;2;255;0;0;44", ""),
+                ("0", "\x1B[0m"),
+            ],
+        );
+    }
+
+    #[test]
+    fn sixteen_colors_take_the_nearest() {
+        each(
+            Theme::Ansi16,
+            &[
+                ("32", "\x1B[32m"),
+                ("38;2;255;0;0", "\x1B[91m"),
+                ("38;2;0;128;0", "\x1B[32m"),
+                ("38;5;196", "\x1B[91m"),
+                ("38;5;21", "\x1B[94m"),
+                ("48;5;236", "\x1B[40m"),
+                ("38;5;250", "\x1B[37m"),
+            ],
+        );
+    }
+
+    #[test]
+    fn high_contrast_is_bright_and_bold() {
+        each(
+            Theme::HighContrast,
+            &[
+                ("31", "\x1B[91;1m"),
+                ("34", "\x1B[96;1m"),
+                ("30", "\x1B[97;1m"),
+                ("41", "\x1B[101m"),
+                ("38;5;88", "\x1B[91;1m"),
+            ],
+        );
+    }
+}
@@ -0,0 +1,1346 @@
+// tui.rs 🦀
+// the full screen front end, built with --features tui & played with --tui
+
+use crossterm::event::{self, Event as TermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::path::PathBuf;
+
+use drugwars::highscores::HighScore;
+use drugwars::{save, Command, Difficulty, Drug, End, Event, Game, Location, Offer};
+
+use super::{hotkey, key, locale, term, theme, Client};
+
+// anything smaller gets a message instead of the game
+const MIN_WIDTH: u16 = 40;
+// the three panels side by side need this much room, & more with the
+// supply & demand beside the prices
+const WIDE: u16 = 80;
+const WIDE_LIMITED: u16 = 96;
+const LOG_LINES: usize = 200;
+
+#[derive(Debug, Clone, Copy)]
+enum Deal {
+    Buy,
+    Sell,
+    Stash,
+    Take,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Deal(Deal, Drug),
+    Repay,
+    Borrow,
+    Deposit,
+    Withdraw,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Service {
+    LoanShark,
+    Bank,
+    Stash,
+}
+
+#[derive(Debug, Clone)]
+enum Mode {
+    Market,
+    Pick(Deal),
+    Amount(Action, String),
+    Service(Service),
+    Jet,
+    // which place the informant tells about
+    Tip,
+    // every drug's price day by day, until any key
+    History,
+    Offer(Offer),
+    Fight,
+    // the pause menu, asked from anywhere, so it remembers where it came from
+    Pause(Box<Mode>),
+    // a forfeit from the pause menu, once more to be sure
+    Forfeit(Box<Mode>),
+    // the goal & the keys, any key goes back to the pause menu
+    Help(Box<Mode>),
+    Name(String),
+    Over,
+}
+
+struct App {
+    game: Game,
+    save_path: Option<PathBuf>,
+    player: Option<String>,
+    mode: Mode,
+    // everything that happened, newest last
+    log: Vec<(Color, String)>,
+    // what went wrong with the last key, until the next one
+    error: Option<String>,
+    // the table the score went into, once the game is over
+    scores: Vec<HighScore>,
+    // the score or profile that couldn't be written
+    troubles: Vec<String>,
+    done: bool,
+}
+
+// a new game is started here, a loaded one carries on where it was saved
+pub fn run(
+    game: Game,
+    save_path: Option<PathBuf>,
+    player: Option<String>,
+    fresh: bool,
+) -> io::Result<()> {
+    let mut app = App {
+        game,
+        save_path,
+        player,
+        mode: Mode::Market,
+        log: Vec::new(),
+        error: None,
+        scores: Vec::new(),
+        troubles: Vec::new(),
+        done: false,
+    };
+    app.arrive();
+    if fresh {
+        let events = app.game.start();
+        app.show(events);
+    }
+    app.settle();
+
+    let mut terminal = ratatui::init();
+    term::alternate(true);
+    let result = app.main_loop(&mut terminal);
+    ratatui::restore();
+    term::alternate(false);
+    result
+}
+
+fn color(name: &str) -> Color {
+    match name.to_ascii_lowercase().as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        hex => hex
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .map_or(Color::Reset, Color::from_u32),
+    }
+}
+
+// breaks a message on spaces so the log can count its rows, counting
+// characters so an accent doesn't take two columns
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in text.split_whitespace() {
+        let line = lines.last_mut().unwrap();
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(word.to_string());
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    }
+    lines
+}
+
+fn bold(color: Color) -> Style {
+    Style::new().fg(color).add_modifier(Modifier::BOLD)
+}
+
+// a choice & its hotkey, the way the prompt's second line shows it
+fn choice(name: &str) -> String {
+    format!("{}) {}", hotkey(name), locale().text(name))
+}
+
+// a message from the locale with one of its {name}s styled apart
+fn filled(key: &str, name: &str, value: String, style: Style) -> Line<'static> {
+    let text = locale().text(key);
+    let (before, after) = text
+        .split_once(&format!("{{{name}}}"))
+        .unwrap_or((text, ""));
+    Line::from(vec![
+        Span::raw(before.to_string()),
+        Span::styled(value, style),
+        Span::raw(after.to_string()),
+    ])
+}
+
+// wide enough for the numbers & for the heading in any language
+fn column(heading: &str, width: u16) -> Constraint {
+    Constraint::Length(width.max(locale().text(heading).chars().count() as u16 + 1))
+}
+
+fn drug_name(game: &Game, drug: Drug) -> &str {
+    locale().name(game.catalog().name(drug))
+}
+
+// the engine's complaints in the player's language
+fn complaint(e: drugwars::Error) -> String {
+    locale().name(&e.to_string()).to_uppercase()
+}
+
+impl App {
+    // only a key or a resize redraws, & ratatui only writes the cells that
+    // changed since the last frame
+    fn main_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        terminal.draw(|frame| {
+            self.draw(frame);
+            theme::restyle(frame.buffer_mut());
+        })?;
+        while !self.done {
+            match event::read()? {
+                TermEvent::Key(key) if key.kind == KeyEventKind::Press => self.key(key),
+                TermEvent::Resize(..) => {}
+                _ => continue,
+            }
+            terminal.draw(|frame| {
+                self.draw(frame);
+                theme::restyle(frame.buffer_mut());
+            })?;
+        }
+        Ok(())
+    }
+
+    fn note(&mut self, color: Color, text: String) {
+        self.log.push((color, text));
+        if self.log.len() > LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+
+    fn arrive(&mut self) {
+        let text = format!(
+            "{}  {}",
+            Client::game_date_str(self.game.day()),
+            locale().name(self.game.map().name(self.game.location()))
+        );
+        self.note(Color::Blue, text);
+    }
+
+    fn show(&mut self, events: Vec<Event>) {
+        for event in events {
+            if let Event::Unlocked(achievement) = event {
+                if !Client::is_new(self.player.as_deref(), achievement) {
+                    continue;
+                }
+            }
+            let lines: Vec<(Color, String)> = Client::event_lines(&self.game, event)
+                .into_iter()
+                .map(|(name, text)| (color(name), text))
+                .collect();
+            for (color, text) in lines {
+                self.note(color, text);
+            }
+        }
+    }
+
+    // works out what the engine is waiting on, like the classic client's play
+    fn settle(&mut self) {
+        self.mode = if self.game.is_over() {
+            Mode::Name(String::new())
+        } else if let Some(offer) = self.game.offer() {
+            Mode::Offer(offer)
+        } else if self.game.in_fight() {
+            Mode::Fight
+        } else {
+            Mode::Market
+        };
+    }
+
+    fn apply(&mut self, command: Command) {
+        match self.game.apply(command) {
+            Ok(events) => {
+                self.show(events);
+                self.settle();
+            }
+            Err(e) => {
+                self.error = Some(complaint(e));
+                self.settle();
+            }
+        }
+    }
+
+    fn key(&mut self, press: KeyEvent) {
+        self.error = None;
+        if press.modifiers.contains(KeyModifiers::CONTROL) && press.code == KeyCode::Char('c') {
+            self.mode = match self.mode {
+                Mode::Over => {
+                    self.done = true;
+                    Mode::Over
+                }
+                Mode::Name(_) => {
+                    self.finish(String::new());
+                    Mode::Over
+                }
+                // a second ctrl-c walks away without a save or a score
+                Mode::Pause(_) | Mode::Forfeit(_) | Mode::Help(_) => {
+                    self.done = true;
+                    return;
+                }
+                _ => Mode::Pause(Box::new(self.mode.clone())),
+            };
+            return;
+        }
+        let c = match press.code {
+            KeyCode::Char(c) => c.to_ascii_lowercase(),
+            KeyCode::Esc => '\x1B',
+            KeyCode::Enter => '\r',
+            KeyCode::Backspace => '\x08',
+            _ => return,
+        };
+
+        match self.mode.clone() {
+            Mode::Market => self.market(c),
+            Mode::Pick(deal) => {
+                self.mode = match self.game.catalog().from_key(c) {
+                    Some(drug)
+                        if matches!(deal, Deal::Buy | Deal::Sell) && self.game.none_today(drug) =>
+                    {
+                        let name = drug_name(&self.game, drug);
+                        self.error = Some(locale().fill("none_today", &[("drug", &name)]));
+                        Mode::Market
+                    }
+                    Some(drug) => Mode::Amount(Action::Deal(deal, drug), String::new()),
+                    None => Mode::Market,
+                }
+            }
+            Mode::Amount(action, mut typed) => match c {
+                _ if (c.is_ascii_digit() || "kmaxe.,".contains(c)) && typed.len() < 16 => {
+                    typed.push(c);
+                    self.mode = Mode::Amount(action, typed);
+                }
+                '\x08' => {
+                    typed.pop();
+                    self.mode = Mode::Amount(action, typed);
+                }
+                '\x1B' => self.mode = Mode::Market,
+                '\r' => {
+                    let (max, capped) = self.limit(action);
+                    match Client::parse_amount(&typed, max, capped) {
+                        Ok(0) => self.mode = Mode::Market,
+                        Ok(amount) => self.apply(Self::command(action, amount)),
+                        // stays on the amount so it can be fixed
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                _ => {}
+            },
+            Mode::Service(service) => {
+                let action = match service {
+                    Service::LoanShark if c == key("repay") => Some(Action::Repay),
+                    Service::LoanShark if c == key("borrow") => Some(Action::Borrow),
+                    Service::Bank if c == key("deposit") => Some(Action::Deposit),
+                    Service::Bank if c == key("withdraw") => Some(Action::Withdraw),
+                    Service::Stash if c == key("deposit") => {
+                        self.mode = Mode::Pick(Deal::Stash);
+                        return;
+                    }
+                    Service::Stash if c == key("take") => {
+                        self.mode = Mode::Pick(Deal::Take);
+                        return;
+                    }
+                    _ => None,
+                };
+                self.mode = match action {
+                    Some(action) => Mode::Amount(action, String::new()),
+                    None => Mode::Market,
+                };
+            }
+            Mode::Jet => match self.game.map().from_key(c) {
+                Some(location) => match self.game.apply(Command::Jet(location)) {
+                    Ok(events) => {
+                        // the last jet ends the game, there's nowhere to arrive
+                        if !self.game.is_over() {
+                            self.arrive();
+                        }
+                        self.show(events);
+                        self.settle();
+                    }
+                    Err(e) => {
+                        self.error = Some(complaint(e));
+                        self.settle();
+                    }
+                },
+                _ => self.mode = Mode::Market,
+            },
+            Mode::Tip => match self.game.map().from_key(c) {
+                Some(location) => {
+                    self.apply(Command::Tip(location));
+                    if self.error.is_none() {
+                        self.note_tip(location);
+                    }
+                }
+                _ => self.mode = Mode::Market,
+            },
+            Mode::History => self.mode = Mode::Market,
+            Mode::Offer(_) => match c {
+                _ if c == key("yes") => self.apply(Command::Accept),
+                _ if c == key("no") => self.apply(Command::Decline),
+                _ if self.pauses(c) => self.mode = Mode::Pause(Box::new(self.mode.clone())),
+                _ => {}
+            },
+            Mode::Fight => match c {
+                _ if c == key("run") => self.apply(Command::Run),
+                _ if c == key("fight") && self.game.guns() > 0 => self.apply(Command::Fight),
+                _ if self.pauses(c) => self.mode = Mode::Pause(Box::new(Mode::Fight)),
+                _ => {}
+            },
+            Mode::Pause(back) => match c {
+                _ if c == key("resume") || c == '\x1B' => self.mode = *back,
+                _ if c == key("save_quit") => {
+                    self.save();
+                    self.done = self.error.is_none();
+                }
+                _ if c == key("forfeit") => self.mode = Mode::Forfeit(back),
+                _ if c == key("help") => self.mode = Mode::Help(back),
+                _ => {}
+            },
+            // a forfeit keeps the player, there's no name to ask for
+            Mode::Forfeit(back) => match c {
+                _ if c == key("yes") => {
+                    self.apply(Command::Forfeit);
+                    if self.game.is_over() {
+                        self.finish(String::new());
+                    }
+                }
+                _ if c == key("no") || c == '\x1B' => self.mode = Mode::Pause(back),
+                _ => {}
+            },
+            Mode::Help(back) => self.mode = Mode::Pause(back),
+            Mode::Name(mut typed) => match c {
+                '\r' => self.finish(typed),
+                '\x08' => {
+                    typed.pop();
+                    self.mode = Mode::Name(typed);
+                }
+                _ if !c.is_control() && typed.len() < 12 => {
+                    typed.push(c.to_ascii_uppercase());
+                    self.mode = Mode::Name(typed);
+                }
+                _ => {}
+            },
+            Mode::Over => {
+                if c == key("quit") || c == '\x1B' || c == '\r' {
+                    self.done = true;
+                }
+            }
+        }
+    }
+
+    fn market(&mut self, c: char) {
+        let here = self.game.here();
+        self.mode = match c {
+            _ if c == key("buy") => Mode::Pick(Deal::Buy),
+            _ if c == key("sell") => Mode::Pick(Deal::Sell),
+            _ if c == key("jet") => Mode::Jet,
+            _ if c == key("loan_shark") && here.loan_shark => {
+                let _ = self.game.apply(Command::LoanShark);
+                Mode::Service(Service::LoanShark)
+            }
+            _ if c == key("bank") && here.bank => Mode::Service(Service::Bank),
+            _ if c == key("stash") && here.stash => Mode::Service(Service::Stash),
+            _ if c == key("informant") && here.informant => Mode::Tip,
+            _ if c == key("history") => Mode::History,
+            _ if c == key("save") => {
+                self.save();
+                Mode::Market
+            }
+            _ if self.pauses(c) => Mode::Pause(Box::new(Mode::Market)),
+            _ => Mode::Market,
+        };
+    }
+
+    fn pauses(&self, c: char) -> bool {
+        c == '\x1B' || c == key("pause")
+    }
+
+    fn note_tip(&mut self, location: Location) {
+        let game = &self.game;
+        let Some(seen) = game.seen(location) else {
+            return;
+        };
+        let prices: Vec<String> = game
+            .catalog()
+            .drugs()
+            .filter(|drug| seen.price(*drug) > 0)
+            .map(|drug| {
+                let price = Client::format_number(seen.price(drug));
+                format!("{} {}", drug_name(game, drug), price)
+            })
+            .collect();
+        let place = locale().name(game.map().name(location));
+        let text = format!(
+            "{} {}",
+            locale().fill("tip", &[("place", &place)]),
+            prices.join(", ")
+        );
+        self.note(Color::Green, text);
+    }
+
+    fn save(&mut self) {
+        let Some(path) = self.save_path.clone() else {
+            self.error = Some(locale().text("nowhere_to_save").to_string());
+            return;
+        };
+        match save::save(&self.game, &path) {
+            Ok(()) => {
+                let text = locale().fill("saved", &[("path", &path.display())]);
+                self.note(Color::Green, text);
+            }
+            Err(e) => self.error = Some(locale().fill("cant_save", &[("error", &e)])),
+        }
+    }
+
+    // what m fills in, the same limits as the classic client
+    fn limit(&self, action: Action) -> (i64, bool) {
+        let game = &self.game;
+        let max = match action {
+            Action::Deal(Deal::Buy, drug) => {
+                let supply = game.stock(drug).map_or(i64::MAX, |stock| stock.supply);
+                game.afford(drug).min(game.hold()).min(supply)
+            }
+            Action::Deal(Deal::Sell, drug) => {
+                let demand = game.stock(drug).map_or(i64::MAX, |stock| stock.demand);
+                game.trench_coat(drug).min(demand)
+            }
+            Action::Deal(Deal::Stash, drug) => game.trench_coat(drug),
+            Action::Deal(Deal::Take, drug) => game.stash(drug).min(game.hold()),
+            Action::Repay => game.cash().min(game.debt()),
+            Action::Borrow => return ((game.max_loan() - game.debt()).max(0), false),
+            Action::Deposit => game.cash(),
+            Action::Withdraw => game.bank(),
+        };
+        (max.max(0), true)
+    }
+
+    fn command(action: Action, amount: i64) -> Command {
+        match action {
+            Action::Deal(Deal::Buy, drug) => Command::Buy { drug, amount },
+            Action::Deal(Deal::Sell, drug) => Command::Sell { drug, amount },
+            Action::Deal(Deal::Stash, drug) => Command::Stash { drug, amount },
+            Action::Deal(Deal::Take, drug) => Command::Take { drug, amount },
+            Action::Repay => Command::Repay(amount),
+            Action::Borrow => Command::Borrow(amount),
+            Action::Deposit => Command::Deposit(amount),
+            Action::Withdraw => Command::Withdraw(amount),
+        }
+    }
+
+    // an empty name keeps the current player, like the classic client
+    fn finish(&mut self, typed: String) {
+        let name = if typed.trim().is_empty() {
+            self.player.clone().unwrap_or_default()
+        } else {
+            typed.trim().to_string()
+        };
+        let (scores, troubles) = Client::record_score(&self.game, name.clone(), true);
+        self.troubles = troubles;
+        if !name.is_empty() {
+            self.player = Some(name);
+        }
+        let difficulty = self.game.rules().difficulty;
+        self.scores = scores
+            .into_iter()
+            .filter(|hs| hs.difficulty == difficulty)
+            .collect();
+        self.mode = Mode::Over;
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let drugs = self.game.catalog().len() as u16;
+        // the compact layout needs a status line, a table, a log line & the prompt
+        if area.width < MIN_WIDTH || area.height < drugs + 7 {
+            let text = vec![
+                Line::styled(locale().text("too_small"), bold(Color::Red)),
+                Line::from(
+                    locale().fill("need", &[("width", &MIN_WIDTH), ("height", &(drugs + 7))]),
+                ),
+            ];
+            let [_, middle, _] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(2),
+                Constraint::Fill(1),
+            ])
+            .areas(area);
+            frame.render_widget(Paragraph::new(text).centered(), middle);
+            return;
+        }
+        if matches!(self.mode, Mode::Name(_) | Mode::Over) {
+            self.draw_over(frame, area);
+            return;
+        }
+
+        let panels = (drugs + 3).max(9);
+        let wide = if self.game.rules().limited {
+            WIDE_LIMITED
+        } else {
+            WIDE
+        };
+        if area.width >= wide && area.height >= 1 + panels + 3 + 2 {
+            let [status, top, log, prompt] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(panels),
+                Constraint::Fill(1),
+                Constraint::Length(2),
+            ])
+            .areas(area);
+            // the supply & demand need the prices to be the widest
+            let (left, middle) = if self.game.rules().limited {
+                (3, 4)
+            } else {
+                (4, 3)
+            };
+            let [inventory, prices, finances] = Layout::horizontal([
+                Constraint::Fill(left),
+                Constraint::Fill(middle),
+                Constraint::Fill(3),
+            ])
+            .areas(top);
+            self.draw_status(frame, status);
+            self.draw_inventory(frame, inventory);
+            self.draw_prices(frame, prices);
+            self.draw_finances(frame, finances);
+            self.draw_below(frame, log, true);
+            self.draw_prompt(frame, prompt);
+        } else {
+            let [status, money, table, log, prompt] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(drugs + 1),
+                Constraint::Fill(1),
+                Constraint::Length(3),
+            ])
+            .areas(area);
+            self.draw_status(frame, status);
+            self.draw_money(frame, money);
+            self.draw_market(frame, table);
+            self.draw_below(frame, log, false);
+            self.draw_prompt(frame, prompt);
+        }
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let mut spans = vec![
+            Span::styled(
+                format!("{}  ", locale().text("drug_wars")),
+                bold(Color::Green),
+            ),
+            Span::raw(Client::game_date_str(game.day())),
+            Span::raw(format!("  {} ", locale().text("days_left"))),
+            Span::styled(
+                format!("{:02}", game.days_left()),
+                Style::new().fg(Color::Magenta),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                locale().name(game.map().name(game.location())).to_string(),
+                Style::new().fg(Color::Blue),
+            ),
+        ];
+        let difficulty = game.rules().difficulty;
+        if difficulty != Difficulty::Classic {
+            spans.push(Span::raw(format!(
+                "  {}",
+                Client::difficulty_name(difficulty)
+            )));
+        }
+        if game.in_fight() {
+            spans.push(Span::styled(
+                format!("  {} {}", locale().text("cops"), game.cops()),
+                bold(Color::Red),
+            ));
+        }
+        frame.render_widget(Line::from(spans), area);
+    }
+
+    fn drug_cell(&self, drug: Drug) -> Cell<'_> {
+        let spec = self.game.catalog().spec(drug);
+        Cell::from(format!(
+            "{}) {}",
+            spec.key.to_ascii_uppercase(),
+            locale().name(&spec.name)
+        ))
+        .style(Style::new().fg(color(&spec.color)))
+    }
+
+    fn price(&self, drug: Drug) -> String {
+        if self.game.sold_here(drug) {
+            Client::format_number(self.game.price(drug))
+        } else if self.game.none_today(drug) {
+            locale().text("none").to_string()
+        } else {
+            "-".to_string()
+        }
+    }
+
+    // what's left to buy & sell, only when drugs are limited
+    fn stock_cells(&self, drug: Drug) -> Vec<Cell<'_>> {
+        if !self.game.rules().limited {
+            return Vec::new();
+        }
+        let stock = self.game.stock(drug);
+        let cell = |n: Option<i64>| Cell::from(n.map_or("-".to_string(), |n| n.to_string()));
+        vec![
+            cell(stock.map(|stock| stock.supply)),
+            cell(stock.map(|stock| stock.demand)),
+        ]
+    }
+
+    // the headings & widths the stock adds to a table of drugs
+    fn stock_columns(&self, keys: &mut Vec<&str>, widths: &mut Vec<Constraint>) {
+        if self.game.rules().limited {
+            keys.extend(["supply", "demand"]);
+            widths.extend([column("supply", 5), column("demand", 5)]);
+        }
+    }
+
+    fn draw_inventory(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let rows = game.catalog().drugs().map(|drug| {
+            Row::new(vec![
+                self.drug_cell(drug),
+                Cell::from(game.stash(drug).to_string()),
+                Cell::from(game.trench_coat(drug).to_string()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [Constraint::Fill(1), column("stash", 7), column("coat", 7)],
+        )
+        .header(
+            Row::new(["drug", "stash", "coat"].map(|key| locale().text(key)))
+                .style(Style::new().fg(Color::Cyan)),
+        )
+        .block(Block::bordered().title(format!(
+            " {}  {} {} ",
+            locale().text("inventory"),
+            locale().text("hold"),
+            game.hold()
+        )));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_prices(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.game.catalog().drugs().map(|drug| {
+            let mut cells = vec![self.drug_cell(drug), Cell::from(self.price(drug))];
+            cells.extend(self.stock_cells(drug));
+            Row::new(cells)
+        });
+        let mut keys = vec!["drug", "price"];
+        let mut widths = vec![Constraint::Fill(1), column("price", 8)];
+        self.stock_columns(&mut keys, &mut widths);
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(keys.into_iter().map(|key| locale().text(key)))
+                    .style(Style::new().fg(Color::Cyan)),
+            )
+            .block(Block::bordered().title(format!(" {} ", locale().text("prices_title"))));
+        frame.render_widget(table, area);
+    }
+
+    fn draw_finances(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let row = |label: &str, value: String, color: Color| {
+            Line::from(vec![
+                Span::raw(format!("{:<11}", locale().text(label))),
+                Span::styled(value, Style::new().fg(color)),
+            ])
+        };
+        let lines = vec![
+            row("cash", Client::format_number(game.cash()), Color::Green),
+            row("bank", Client::format_number(game.bank()), Color::Cyan),
+            row("debt", Client::format_number(game.debt()), Color::Magenta),
+            row("guns", game.guns().to_string(), Color::Blue),
+            row(
+                "damage",
+                locale().fill(
+                    "of",
+                    &[("n", &game.damage()), ("count", &game.rules().max_damage)],
+                ),
+                Color::Red,
+            ),
+            row(
+                "net_worth",
+                Client::format_number(game.net_worth()),
+                Color::Yellow,
+            ),
+        ];
+        let title = format!(" {} ", locale().text("finances"));
+        let paragraph = Paragraph::new(lines).block(Block::bordered().title(title));
+        frame.render_widget(paragraph, area);
+    }
+
+    // the finances squeezed onto one line for small terminals
+    fn draw_money(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let line = Line::from(vec![
+            Span::raw(format!("{} ", locale().text("cash"))),
+            Span::styled(
+                Client::format_number(game.cash()),
+                Style::new().fg(Color::Green),
+            ),
+            Span::raw(format!(" {} ", locale().text("bank"))),
+            Span::styled(
+                Client::format_number(game.bank()),
+                Style::new().fg(Color::Cyan),
+            ),
+            Span::raw(format!(" {} ", locale().text("debt"))),
+            Span::styled(
+                Client::format_number(game.debt()),
+                Style::new().fg(Color::Magenta),
+            ),
+            Span::raw(format!(
+                " {} {} {} {}",
+                locale().text("guns"),
+                game.guns(),
+                locale().text("hold"),
+                game.hold()
+            )),
+        ]);
+        frame.render_widget(line, area);
+    }
+
+    // inventory & prices in one table for small terminals
+    fn draw_market(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let rows = game.catalog().drugs().map(|drug| {
+            let mut cells = vec![
+                self.drug_cell(drug),
+                Cell::from(self.price(drug)),
+                Cell::from(game.trench_coat(drug).to_string()),
+                Cell::from(game.stash(drug).to_string()),
+            ];
+            cells.extend(self.stock_cells(drug));
+            Row::new(cells)
+        });
+        let mut keys = vec!["drug", "price", "coat", "stash"];
+        let mut widths = vec![
+            Constraint::Fill(1),
+            column("price", 8),
+            column("coat", 6),
+            column("stash", 6),
+        ];
+        self.stock_columns(&mut keys, &mut widths);
+        let table = Table::new(rows, widths).header(
+            Row::new(keys.into_iter().map(|key| locale().text(key)))
+                .style(Style::new().fg(Color::Cyan)),
+        );
+        frame.render_widget(table, area);
+    }
+
+    // where you're going, the prices last seen there take the log's place,
+    // & so does the history
+    fn draw_below(&self, frame: &mut Frame, area: Rect, bordered: bool) {
+        match self.mode {
+            Mode::Jet | Mode::Tip => self.draw_seen(frame, area, bordered),
+            Mode::History => self.draw_history(frame, area, bordered),
+            Mode::Help(_) => self.draw_help(frame, area, bordered),
+            _ => self.draw_log(frame, area, bordered),
+        }
+    }
+
+    fn draw_help(&self, frame: &mut Frame, area: Rect, bordered: bool) {
+        let block = if bordered {
+            Block::bordered().title(format!(" {} ", locale().text("help_title")))
+        } else {
+            Block::new()
+        };
+        let lines: Vec<Line> = Client::help_lines(&self.game)
+            .into_iter()
+            .map(|(heading, text)| {
+                if heading.is_empty() {
+                    Line::raw(text)
+                } else {
+                    Line::from(vec![
+                        Span::styled(format!("{heading:<15} "), Style::new().fg(Color::Cyan)),
+                        Span::raw(text),
+                    ])
+                }
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(block).wrap(Wrap { trim: true }),
+            area,
+        );
+    }
+
+    // as many of the last days as fit, a block a day
+    fn draw_history(&self, frame: &mut Frame, area: Rect, bordered: bool) {
+        let game = &self.game;
+        let history = game.history();
+        let inner = if bordered {
+            area.width.saturating_sub(2)
+        } else {
+            area.width
+        };
+        let widths = [
+            column("drug", 11),
+            column("low", 9),
+            column("high", 9),
+            column("average", 9),
+        ];
+        let fixed: u16 = widths
+            .iter()
+            .map(|width| match width {
+                Constraint::Length(n) => n + 1,
+                _ => 0,
+            })
+            .sum();
+        let room = inner.saturating_sub(fixed).max(1) as i64;
+        let first = (game.day() + 1 - room).max(0);
+        let days = locale().fill(
+            "history_days",
+            &[("first", &(first + 1)), ("last", &(game.day() + 1))],
+        );
+        let places: String = history
+            .places(game.day())
+            .into_iter()
+            .skip(first as usize)
+            .map(|place| {
+                place.map_or(' ', |place| {
+                    char::from_digit(place.index() as u32 + 1, 36).unwrap_or('+')
+                })
+            })
+            .collect();
+
+        let cell = |price: Option<i64>| price.map_or("-".to_string(), Client::format_number);
+        let mut rows = vec![Row::new(vec![
+            Cell::from(locale().text("where")),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(places).style(Style::new().fg(Color::Blue)),
+        ])];
+        for drug in game.catalog().drugs() {
+            let spread = history.spread(drug);
+            let prices = history.daily(drug, game.day());
+            rows.push(Row::new(vec![
+                Cell::from(drug_name(game, drug).to_string()),
+                Cell::from(cell(spread.map(|spread| spread.low)))
+                    .style(Style::new().fg(Color::Green)),
+                Cell::from(cell(spread.map(|spread| spread.high)))
+                    .style(Style::new().fg(Color::Red)),
+                Cell::from(cell(spread.map(|spread| spread.average)))
+                    .style(Style::new().fg(Color::Yellow)),
+                Cell::from(Client::sparkline(&prices[first as usize..])),
+            ]));
+        }
+        let heading = ["drug", "low", "high", "average"].map(|key| locale().text(key).to_string());
+        let mut heading = heading.to_vec();
+        heading.push(days);
+        let mut widths = widths.to_vec();
+        widths.push(Constraint::Fill(1));
+        let mut table =
+            Table::new(rows, widths).header(Row::new(heading).style(Style::new().fg(Color::Cyan)));
+        if bordered {
+            table = table
+                .block(Block::bordered().title(format!(" {} ", locale().text("history_title"))));
+        }
+        frame.render_widget(table, area);
+    }
+
+    fn draw_seen(&self, frame: &mut Frame, area: Rect, bordered: bool) {
+        let game = &self.game;
+        let cell = |price: i64| {
+            if price > 0 {
+                Client::format_number(price)
+            } else {
+                "-".to_string()
+            }
+        };
+        let place = |location| locale().name(game.map().name(location)).to_string();
+        let mut rows = vec![(
+            place(game.location()),
+            locale().text("today").to_string(),
+            game.prices()
+                .row(game.location())
+                .into_iter()
+                .map(cell)
+                .collect::<Vec<_>>(),
+        )];
+        for location in game.map().locations().filter(|l| *l != game.location()) {
+            if let Some(seen) = game.seen(location) {
+                let age = Client::age(game.day(), seen.day);
+                let prices = game.catalog().drugs().map(|drug| cell(seen.price(drug)));
+                rows.push((place(location), age, prices.collect()));
+            }
+        }
+
+        // the border says it already
+        let title = if bordered {
+            ""
+        } else {
+            locale().text("last_seen")
+        };
+        let width = |n: usize| Constraint::Length(n as u16);
+        let name_width = rows
+            .iter()
+            .map(|row| row.0.chars().count())
+            .chain([title.chars().count()])
+            .max()
+            .unwrap_or(0);
+        let age_width = rows
+            .iter()
+            .map(|row| row.1.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut widths = vec![width(name_width), width(age_width)];
+        let mut heading = vec![title.to_string(), String::new()];
+        for drug in game.catalog().drugs() {
+            let name: String = drug_name(game, drug).chars().take(7).collect();
+            widths.push(width(name.chars().count().max(6)));
+            heading.push(name);
+        }
+        let rows = rows.into_iter().map(|(name, age, prices)| {
+            let mut cells = vec![
+                Cell::from(name).style(Style::new().fg(Color::Blue)),
+                Cell::from(age).style(Style::new().fg(Color::Yellow)),
+            ];
+            cells.extend(prices.into_iter().map(Cell::from));
+            Row::new(cells)
+        });
+        let mut table =
+            Table::new(rows, widths).header(Row::new(heading).style(Style::new().fg(Color::Cyan)));
+        if bordered {
+            table =
+                table.block(Block::bordered().title(format!(" {} ", locale().text("last_seen"))));
+        }
+        frame.render_widget(table, area);
+    }
+
+    fn draw_log(&self, frame: &mut Frame, area: Rect, bordered: bool) {
+        let block = if bordered {
+            Block::bordered().title(format!(" {} ", locale().text("log")))
+        } else {
+            Block::new()
+        };
+        let inner = block.inner(area);
+        let width = (inner.width as usize).max(1);
+        let mut lines: Vec<Line> = Vec::new();
+        // newest at the bottom, older lines scroll off the top
+        for (color, text) in self.log.iter().rev() {
+            for line in wrap(text, width).into_iter().rev() {
+                lines.push(Line::styled(line, Style::new().fg(*color)));
+            }
+            if lines.len() >= inner.height as usize {
+                break;
+            }
+        }
+        lines.truncate(inner.height as usize);
+        lines.reverse();
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn draw_prompt(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let (question, keys) = match &self.mode {
+            Mode::Market => {
+                let here = game.here();
+                let mut keys = vec![choice("buy"), choice("sell"), choice("jet")];
+                if here.loan_shark {
+                    keys.push(choice("loan_shark"));
+                }
+                if here.bank {
+                    keys.push(choice("bank"));
+                }
+                if here.stash {
+                    keys.push(choice("stash"));
+                }
+                if here.informant {
+                    keys.push(choice("informant"));
+                }
+                keys.extend([choice("history"), choice("save")]);
+                keys.push(locale().fill("help_pause", &[("pause", &hotkey("pause"))]));
+                (locale().text("buy_sell_jet").to_string(), keys.join("  "))
+            }
+            Mode::Pick(deal) => {
+                let verb = match deal {
+                    Deal::Buy => "buy",
+                    Deal::Sell => "sell",
+                    Deal::Stash => "stash",
+                    Deal::Take => "take",
+                };
+                (
+                    locale().fill("what_deal", &[("deal", &locale().text(verb))]),
+                    locale().text("press_drug_key").to_string(),
+                )
+            }
+            Mode::Amount(action, typed) => {
+                let question = match action {
+                    Action::Deal(Deal::Buy, drug) => locale().fill(
+                        "how_much_buy_of",
+                        &[
+                            ("drug", &drug_name(game, *drug)),
+                            ("n", &game.afford(*drug)),
+                        ],
+                    ),
+                    Action::Deal(Deal::Sell, drug) => locale().fill(
+                        "how_much_sell_of",
+                        &[
+                            ("drug", &drug_name(game, *drug)),
+                            ("n", &game.trench_coat(*drug)),
+                        ],
+                    ),
+                    Action::Deal(Deal::Stash, drug) => {
+                        locale().fill("how_much_stash", &[("drug", &drug_name(game, *drug))])
+                    }
+                    Action::Deal(Deal::Take, drug) => {
+                        locale().fill("how_much_take", &[("drug", &drug_name(game, *drug))])
+                    }
+                    Action::Repay => locale().text("how_much_repay").to_string(),
+                    Action::Borrow => locale().text("how_much_borrow").to_string(),
+                    Action::Deposit => locale().text("how_much_deposit").to_string(),
+                    Action::Withdraw => locale().text("how_much_withdraw").to_string(),
+                };
+                (
+                    format!("{question} {typed}_"),
+                    format!(
+                        "ENTER) {}  M) {}  ESC) {}",
+                        locale().text("ok"),
+                        locale().text("max"),
+                        locale().text("cancel")
+                    ),
+                )
+            }
+            Mode::Service(Service::LoanShark) => (
+                locale().text("loan_shark_waiting").to_string(),
+                format!(
+                    "{}  {}  ESC) {}",
+                    choice("repay"),
+                    choice("borrow"),
+                    locale().text("back")
+                ),
+            ),
+            Mode::Service(Service::Bank) => (
+                locale().text("bank_open").to_string(),
+                format!(
+                    "{}  {}  ESC) {}",
+                    choice("deposit"),
+                    choice("withdraw"),
+                    locale().text("back")
+                ),
+            ),
+            Mode::Service(Service::Stash) => (
+                locale().text("your_stash").to_string(),
+                format!(
+                    "{}) {}  {}) {}  ESC) {}",
+                    hotkey("deposit"),
+                    locale().text("stash_drugs"),
+                    hotkey("take"),
+                    locale().text("take_drugs"),
+                    locale().text("back")
+                ),
+            ),
+            Mode::Jet => {
+                let map = game.map();
+                let keys: Vec<String> = map
+                    .locations()
+                    .map(|location| {
+                        format!(
+                            "{}) {}",
+                            location.index() + 1,
+                            locale().name(map.name(location))
+                        )
+                    })
+                    .collect();
+                (locale().text("where_to_tui").to_string(), keys.join("  "))
+            }
+            Mode::Tip => {
+                let map = game.map();
+                let mut keys: Vec<String> = map
+                    .locations()
+                    .filter(|location| *location != game.location())
+                    .map(|location| {
+                        format!(
+                            "{}) {}",
+                            location.index() + 1,
+                            locale().name(map.name(location))
+                        )
+                    })
+                    .collect();
+                keys.push(format!("ESC) {}", locale().text("back")));
+                let price = Client::format_number(game.rules().informant);
+                (
+                    locale().fill("tip_where", &[("price", &price)]),
+                    keys.join("  "),
+                )
+            }
+            Mode::History => (
+                locale().text("history_title").to_string(),
+                locale().text("hit_any_key").to_string(),
+            ),
+            Mode::Offer(offer) => (
+                Client::offer_prompt(*offer).1,
+                format!("{}  {}", choice("yes"), choice("no")),
+            ),
+            Mode::Fight if game.guns() == 0 => {
+                (locale().text("will_you_run").to_string(), choice("run"))
+            }
+            Mode::Fight => (
+                locale().text("run_or_fight").to_string(),
+                format!("{}  {}", choice("run"), choice("fight")),
+            ),
+            Mode::Pause(_) => {
+                let keys: Vec<String> = ["resume", "save_quit", "forfeit", "help"]
+                    .into_iter()
+                    .map(choice)
+                    .collect();
+                (locale().text("paused").to_string(), keys.join("  "))
+            }
+            Mode::Forfeit(_) => (
+                locale().text("forfeit_confirm").to_string(),
+                format!("{}  {}", choice("yes"), choice("no")),
+            ),
+            Mode::Help(_) => (
+                locale().text("help_title").to_string(),
+                locale().text("hit_any_key").to_string(),
+            ),
+            Mode::Name(_) | Mode::Over => (String::new(), String::new()),
+        };
+        let question = match &self.error {
+            Some(error) => Line::styled(error.clone(), bold(Color::Red)),
+            None => Line::styled(question, Style::new().fg(Color::Yellow)),
+        };
+        let keys = Line::styled(keys, Style::new().fg(Color::Cyan));
+        // long lists of keys wrap in the compact layout
+        frame.render_widget(
+            Paragraph::new(vec![question, keys]).wrap(Wrap { trim: true }),
+            area,
+        );
+    }
+
+    fn draw_over(&self, frame: &mut Frame, area: Rect) {
+        let game = &self.game;
+        let score = game.score();
+        // how it ended, the wasted or the paraquat doesn't get a screen of its own
+        let mut lines: Vec<Line> = self.log[self.log.len().saturating_sub(3)..]
+            .iter()
+            .map(|(color, text)| Line::styled(text.clone(), Style::new().fg(*color)))
+            .collect();
+        let yellow = Style::new().fg(Color::Yellow);
+        lines.extend([
+            Line::raw(""),
+            Line::styled(locale().text("game_over"), bold(Color::from_u32(0xffca80))),
+            filled("survived", "days", game.day().to_string(), yellow),
+            filled(
+                "total_money",
+                "money",
+                Client::format_number(score.total_money),
+                Style::new().fg(Color::Green),
+            ),
+            filled(
+                "your_score",
+                "score",
+                score.score.to_string(),
+                Style::new().fg(Color::Magenta),
+            ),
+            filled(
+                "dealer_rank",
+                "rank",
+                format!(
+                    "{} ... {}",
+                    locale().name(score.rank.as_str()),
+                    locale().name(score.rank.motto())
+                ),
+                Style::new().fg(Color::Cyan),
+            ),
+            filled("seed", "seed", game.seed().to_string(), yellow),
+        ]);
+        let difficulty = game.rules().difficulty;
+        if difficulty != Difficulty::Classic {
+            lines.push(filled(
+                "difficulty",
+                "difficulty",
+                Client::difficulty_name(difficulty),
+                yellow,
+            ));
+        }
+        let achievements: Vec<&str> = game
+            .achievements()
+            .iter()
+            .map(|a| locale().name(a.as_str()))
+            .collect();
+        if !achievements.is_empty() {
+            lines.push(filled(
+                "achievements",
+                "names",
+                achievements.join(", "),
+                yellow,
+            ));
+        }
+        for trouble in &self.troubles {
+            lines.push(Line::styled(trouble.clone(), Style::new().fg(Color::Red)));
+        }
+        if game.end() == Some(End::Forfeit) {
+            lines.push(Line::raw(""));
+            lines.push(Line::raw(locale().text("forfeit_note")));
+        }
+        lines.push(Line::raw(""));
+
+        match &self.mode {
+            Mode::Name(typed) => {
+                let prompt = match &self.player {
+                    Some(player) => locale().fill("enter_name_or", &[("player", player)]),
+                    None => locale().text("enter_name").to_string(),
+                };
+                lines.push(Line::styled(
+                    format!("{prompt} {typed}_"),
+                    Style::new().fg(Color::Yellow),
+                ));
+            }
+            _ => {
+                let title = if difficulty == Difficulty::Classic {
+                    locale().text("top_ten").to_string()
+                } else {
+                    let name = Client::difficulty_name(difficulty);
+                    locale().fill("top_ten_of", &[("difficulty", &name)])
+                };
+                lines.push(Line::styled(title, bold(Color::from_u32(0xffca80))));
+                for (i, hs) in self.scores.iter().enumerate() {
+                    lines.push(Line::from(vec![
+                        Span::raw(format!("{:02}  {:<12}  ", i + 1, Client::score_name(hs))),
+                        Span::styled(
+                            Client::format_number(hs.total_money),
+                            Style::new().fg(Color::Green),
+                        ),
+                        Span::raw("  "),
+                        Span::styled(
+                            locale().name(&hs.dealer_rank).to_string(),
+                            Style::new().fg(Color::Cyan),
+                        ),
+                        Span::raw("  "),
+                        Span::styled(hs.date.clone(), Style::new().fg(Color::Magenta)),
+                    ]));
+                }
+                lines.push(Line::raw(""));
+                lines.push(Line::styled(choice("quit"), Style::new().fg(Color::Cyan)));
+            }
+        }
+        frame.render_widget(Paragraph::new(lines).block(Block::bordered()), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_counts_characters() {
+        assert_eq!(wrap("ÁCIDO ÁCIDO", 11), vec!["ÁCIDO ÁCIDO"]);
+        assert_eq!(wrap("ÁCIDO ÁCIDO", 10), vec!["ÁCIDO", "ÁCIDO"]);
+    }
+}
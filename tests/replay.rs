@@ -0,0 +1,193 @@
+// replay.rs 🦀
+// scripted games, played back from their journals, on their own seeds,
+// rules & saves
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+
+use drugwars::game::Command;
+use drugwars::{save, Game};
+
+const RULES: &str = "
+cash = 2000
+debt = 5500
+max_loan = 9450
+days = 2
+hold = 100
+debt_interest = 10
+bank_interest = 5
+max_damage = 50
+";
+
+// no instructions, the opening event, skip the loan shark, stash & bank,
+// buy a cocaine, jet to the ghetto, then central park to the end
+const SCRIPT: &str = "n x n n n b c 1 j 2 n n n n j 3 n n n n n n n n n n";
+
+// a fresh data directory for every test, so nothing leaks between them
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("drugwars-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(dir: &Path, args: &[&str], stdin: &str) -> String {
+    let mut child = Process::new(env!("CARGO_BIN_EXE_drugwars-rust"))
+        .args(args)
+        .env("XDG_DATA_HOME", dir)
+        .env("LANG", "C")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// from the game over to the end, what the game came to
+fn ending(output: &str) -> &str {
+    let start = output.find("GAME OVER").expect("the game never ended");
+    &output[start..]
+}
+
+#[test]
+fn replay_ends_like_the_game() {
+    let dir = scratch("replay");
+    let rules = dir.join("rules.toml");
+    fs::write(&rules, RULES).unwrap();
+    let journal = dir.join("journal.txt");
+
+    let played = run(
+        &dir,
+        &[
+            "--seed",
+            "7",
+            "--rules",
+            rules.to_str().unwrap(),
+            "--market",
+            "drifting",
+            "--limited",
+            "--journal",
+            journal.to_str().unwrap(),
+        ],
+        SCRIPT,
+    );
+    // the journal brings its own rules, market & drugs
+    let replayed = run(&dir, &["--replay", journal.to_str().unwrap()], "");
+
+    assert!(ending(&played).contains("YOUR TOTAL MONEY"));
+    assert_eq!(ending(&played), ending(&replayed));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_seed_plays_the_same_game_twice() {
+    let dir = scratch("seed");
+    let rules = dir.join("rules.toml");
+    fs::write(&rules, RULES).unwrap();
+    let play = |seed| {
+        let rules = rules.to_str().unwrap();
+        let args = [
+            "--seed",
+            seed,
+            "--rules",
+            rules,
+            "--market",
+            "drifting",
+            "--limited",
+        ];
+        run(&dir, &args, SCRIPT)
+    };
+
+    let first = play("7");
+    assert!(ending(&first).contains("YOUR TOTAL MONEY"));
+    assert_eq!(first, play("7"));
+    assert_ne!(first, play("8"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// a game with its own drugs, map or events is custom, whatever the rules
+#[test]
+fn a_modded_game_is_custom() {
+    let dir = scratch("modded");
+    for (flag, file) in [
+        ("--drugs", "drugs"),
+        ("--map", "map"),
+        ("--events", "events"),
+    ] {
+        let journal = dir.join(format!("{file}.txt"));
+        let data = format!("{}/data/{file}.toml", env!("CARGO_MANIFEST_DIR"));
+        let args = [
+            "--seed",
+            "7",
+            flag,
+            &data,
+            "--journal",
+            journal.to_str().unwrap(),
+        ];
+        run(&dir, &args, SCRIPT);
+        let journal = fs::read_to_string(&journal).unwrap();
+        assert!(journal.contains("\"difficulty\":\"custom\""), "{flag}");
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// a headless game picked up from a save never writes it back
+#[test]
+fn a_headless_load_leaves_the_save_alone() {
+    let dir = scratch("load");
+    let path = dir.join("save.json");
+    let mut game = Game::with_seed(7);
+    game.start();
+    while !game.can_save() {
+        game.apply(Command::Decline).unwrap();
+    }
+    save::save(&game, &path).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+
+    // pause, save & quit, then quit
+    let played = run(&dir, &["--load", path.to_str().unwrap()], "q s x q f y");
+    assert!(played.contains("NO PLACE TO SAVE THE GAME"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), saved);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// piped or from a script file, a headless game plays the same & leaves
+// no save, journal, score or profile behind
+#[test]
+fn a_headless_game_leaves_nothing_behind() {
+    let dir = scratch("headless");
+    let script = dir.join("script.txt");
+    fs::write(&script, format!("# the same game\n{SCRIPT}\n")).unwrap();
+    let rules = dir.join("rules.toml");
+    fs::write(&rules, RULES).unwrap();
+    let args = [
+        "--seed",
+        "7",
+        "--rules",
+        rules.to_str().unwrap(),
+        "--market",
+        "drifting",
+        "--limited",
+    ];
+
+    let piped = run(&dir, &args, SCRIPT);
+    let scripted = run(
+        &dir,
+        &[&args[..], &["--script", script.to_str().unwrap()]].concat(),
+        "",
+    );
+    assert!(ending(&piped).contains("YOUR TOTAL MONEY"));
+    assert_eq!(piped, scripted);
+    assert!(!dir.join("drugwars-rust").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}